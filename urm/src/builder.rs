@@ -1,42 +1,156 @@
+use std::fmt::Write;
+
+use std::sync::Arc;
+
 use crate::database::Database;
-use crate::Table;
+use crate::engine::Select;
+use crate::expr::TableAlias;
 
 pub trait Build<DB: Database>: Send + Sync + 'static {
     fn build(&self, builder: &mut QueryBuilder<DB>);
+
+    /// This expression as evaluated in memory by the `testing::MockDatabase`, if it can be.
+    #[cfg(feature = "testing")]
+    fn as_eval(&self) -> Option<&dyn crate::testing::Eval<DB>> {
+        None
+    }
+}
+
+/// A value bound to a placeholder in a built query.
+pub trait Argument<DB: Database>: Send + Sync + 'static {
+    fn bind(&self, arguments: &mut DB::Arguments);
+
+    /// The argument as a JSON value, for inspection.
+    fn to_value(&self) -> serde_json::Value;
+
+    /// Write the placeholder of this argument at the given zero-based index.
+    fn write_placeholder(&self, buf: &mut String, index: usize) {
+        DB::write_placeholder(buf, index);
+    }
+}
+
+/// A built query, ready to be executed.
+pub struct Query<DB: Database> {
+    pub sql: String,
+    pub arguments: Vec<Box<dyn Argument<DB>>>,
+
+    /// The select the query was built from, if any.
+    pub(crate) select: Option<Arc<Select<DB>>>,
+
+    /// Whether arguments are written as SQL literals instead of being bound.
+    pub(crate) inline_arguments: bool,
+}
+
+impl<DB: Database> Query<DB> {
+    pub fn new() -> Self {
+        Self {
+            sql: String::new(),
+            arguments: vec![],
+            select: None,
+            inline_arguments: false,
+        }
+    }
+
+    /// A query where arguments are written as SQL literals, for inspection.
+    pub(crate) fn new_inlined() -> Self {
+        Self {
+            inline_arguments: true,
+            ..Self::new()
+        }
+    }
+
+    /// The bound arguments as JSON values.
+    pub fn argument_values(&self) -> Vec<serde_json::Value> {
+        self.arguments
+            .iter()
+            .map(|argument| argument.to_value())
+            .collect()
+    }
+}
+
+impl<DB: Database> Default for Query<DB> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 pub struct QueryBuilder<'b, DB: Database> {
-    db: std::marker::PhantomData<DB>,
     indent: u16,
-    buf: &'b mut String,
+    query: &'b mut Query<DB>,
 
-    pub table: &'static dyn Table<DB = DB>,
-    pub parent_table: Option<&'static dyn Table<DB = DB>>,
+    pub table: TableAlias<DB>,
+    pub parent_table: Option<TableAlias<DB>>,
 }
 
 impl<'b, DB: Database> QueryBuilder<'b, DB> {
-    pub fn new(table: &'static dyn Table<DB = DB>, buf: &'b mut String) -> Self {
+    pub fn new(table: TableAlias<DB>, query: &'b mut Query<DB>) -> Self {
         Self {
             table,
             parent_table: None,
-            db: std::marker::PhantomData,
             indent: 0,
-            buf,
+            query,
         }
     }
 
-    pub fn push_table(&mut self, table: &'static dyn Table<DB = DB>) -> QueryBuilder<'_, DB> {
+    pub fn push_table(&mut self, table: TableAlias<DB>) -> QueryBuilder<'_, DB> {
         QueryBuilder {
-            db: std::marker::PhantomData,
             indent: self.indent,
-            buf: self.buf,
+            query: self.query,
             table,
-            parent_table: Some(self.table),
+            parent_table: Some(self.table.clone()),
+        }
+    }
+
+    /// Look up the alias of a table in scope, starting with the current table.
+    pub fn table_alias(&self, table_name: &str) -> Option<u16> {
+        Some(&self.table)
+            .into_iter()
+            .chain(self.parent_table.as_ref())
+            .find(|table_alias| table_alias.table.name() == table_name)
+            .map(|table_alias| table_alias.alias)
+    }
+
+    /// Push a qualified reference to a column of some table in scope.
+    pub fn push_column(&mut self, table_name: &str, column: &str) {
+        match self.table_alias(table_name) {
+            Some(alias) => write!(self.query.sql, "a{}", alias).unwrap(),
+            None => DB::write_identifier(&mut self.query.sql, table_name),
+        }
+        self.push(".");
+        self.push_identifier(column);
+    }
+
+    /// Push a quoted identifier.
+    pub fn push_identifier(&mut self, identifier: &str) {
+        DB::write_identifier(&mut self.query.sql, identifier);
+    }
+
+    pub fn push_bool(&mut self, value: bool) {
+        DB::write_bool(&mut self.query.sql, value);
+    }
+
+    /// Push a clause limiting the number of selected rows, on a new line.
+    pub fn push_limit_offset(&mut self, limit: Option<usize>, offset: usize) {
+        if limit.is_none() && offset == 0 {
+            return;
         }
+
+        self.newline();
+        DB::write_limit_offset(&mut self.query.sql, limit, offset);
     }
 
     pub fn buf_mut(&mut self) -> &mut String {
-        &mut self.buf
+        &mut self.query.sql
+    }
+
+    /// Push the placeholder of a new argument to be bound to the query.
+    pub fn push_argument<A: Argument<DB>>(&mut self, argument: A) {
+        if self.query.inline_arguments {
+            DB::write_literal(&mut self.query.sql, &argument.to_value());
+        } else {
+            argument.write_placeholder(&mut self.query.sql, self.query.arguments.len());
+            self.query.arguments.push(Box::new(argument));
+        }
     }
 
     pub fn outdent(&mut self) {
@@ -55,10 +169,10 @@ impl<'b, DB: Database> QueryBuilder<'b, DB> {
 
     pub fn newline(&mut self) {
         self.push("\n");
-        self.buf.extend((0..self.indent).map(|_| ' '));
+        self.query.sql.extend((0..self.indent).map(|_| ' '));
     }
 
     pub fn push(&mut self, str: &str) {
-        self.buf.push_str(str);
+        self.query.sql.push_str(str);
     }
 }
@@ -1,14 +1,17 @@
 //!
-//! Primitive projection of columns/fields.
+//! Primitive projection of columns/fields, stored or computed.
 //!
 
-use crate::builder::{Build, QueryBuilder};
+use std::hash::{Hash, Hasher};
+
+use crate::builder::{Build, Query, QueryBuilder};
 use crate::database::Database;
 use crate::engine::{Probing, QueryField};
+use crate::expr::TableAlias;
 use crate::lower::{Lower, Lowered};
-use crate::project::{LocalId, ProjectAndProbe, ProjectFrom};
+use crate::project::{LocalId, ProjectAndDeserialize, ProjectAndProbe, ProjectFrom, ProjectionKey};
 use crate::ty::{Type, Typed};
-use crate::{Instance, Table, UrmResult};
+use crate::{Instance, Table, UrmError, UrmResult};
 
 pub struct Column<T, Ty> {
     name: &'static str,
@@ -36,6 +39,17 @@ where
     }
 }
 
+impl<T, Ty> Clone for Column<T, Ty> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name,
+            local_id: self.local_id,
+            table: std::marker::PhantomData,
+            ty: std::marker::PhantomData,
+        }
+    }
+}
+
 impl<T, Ty> Typed<T::DB> for Column<T, Ty>
 where
     T: Table,
@@ -60,9 +74,22 @@ where
     Ty: Type,
 {
     fn build(&self, builder: &mut QueryBuilder<T::DB>) {
-        builder.push(T::instance().name());
-        builder.push(".");
-        builder.push(self.name);
+        builder.push_column(T::instance().name(), self.name);
+    }
+
+    #[cfg(feature = "testing")]
+    fn as_eval(&self) -> Option<&dyn crate::testing::Eval<T::DB>> {
+        Some(self)
+    }
+}
+
+#[cfg(feature = "testing")]
+impl<T, Ty> crate::testing::Eval<T::DB> for Column<T, Ty>
+where
+    T: Table + Instance,
+{
+    fn eval(&self, scope: &crate::testing::Scope) -> UrmResult<serde_json::Value> {
+        scope.column(T::instance().name(), self.name)
     }
 }
 
@@ -74,18 +101,304 @@ where
     type Table = T;
 }
 
-impl<DB, T, Out> ProjectAndProbe<DB> for Column<T, Out>
+#[async_trait::async_trait]
+impl<T, Ty> ProjectAndProbe<T::DB> for Column<T, Ty>
+where
+    T: Table + Instance,
+    Ty: Type,
+{
+    async fn project_and_probe(self, probing: &Probing<T::DB>) -> UrmResult<()> {
+        probing
+            .select()
+            .projection
+            .lock()
+            .insert(self.local_id.into(), QueryField::Primitive(Box::new(self)));
+        Ok(())
+    }
+}
+
+impl<T, Ty> ProjectAndDeserialize<T::DB> for Column<T, Ty>
+where
+    T: Table + Instance,
+    Ty: Type,
+    Ty::Output: serde::de::DeserializeOwned,
+{
+    fn project_and_deserialize(self, row: &serde_json::Value) -> UrmResult<Ty::Output> {
+        deserialize_value::<T::DB, Ty>(T::instance().name(), self.name, &self.local_id.into(), row)
+    }
+}
+
+///
+/// A column computed from an SQL expression over other columns of the same table.
+///
+/// Computed columns are declared in `#[urm::table]` using `#[computed(expr)]`,
+/// and otherwise behave like a stored `Column`: They may be projected and used in filters.
+///
+pub struct Computed<T, E> {
+    name: &'static str,
+    local_id: LocalId,
+    expr: E,
+    table: std::marker::PhantomData<T>,
+}
+
+impl<T, E> Computed<T, E>
+where
+    T: Table,
+    E: Lower<T::DB> + Build<T::DB>,
+{
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn new(name: &'static str, local_id: LocalId, expr: E) -> Self {
+        Self {
+            name,
+            local_id,
+            expr,
+            table: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<T, E: Clone> Clone for Computed<T, E> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name,
+            local_id: self.local_id,
+            expr: self.expr.clone(),
+            table: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<T, E> Typed<T::DB> for Computed<T, E>
+where
+    T: Table,
+    E: Typed<T::DB>,
+{
+    type Ty = E::Ty;
+}
+
+impl<T, E> Lower<T::DB> for Computed<T, E>
+where
+    T: Table,
+    E: Lower<T::DB> + Build<T::DB>,
+{
+    fn lower(self) -> Option<Lowered<T::DB>> {
+        Some(Lowered::Expr(Box::new(self)))
+    }
+}
+
+impl<T, E> Build<T::DB> for Computed<T, E>
+where
+    T: Table,
+    E: Lower<T::DB> + Build<T::DB>,
+{
+    fn build(&self, builder: &mut QueryBuilder<T::DB>) {
+        builder.push("(");
+        self.expr.build(builder);
+        builder.push(")");
+    }
+
+    #[cfg(feature = "testing")]
+    fn as_eval(&self) -> Option<&dyn crate::testing::Eval<T::DB>> {
+        Some(self)
+    }
+}
+
+#[cfg(feature = "testing")]
+impl<T, E> crate::testing::Eval<T::DB> for Computed<T, E>
+where
+    T: Table,
+    E: Build<T::DB>,
+{
+    fn eval(&self, scope: &crate::testing::Scope) -> UrmResult<serde_json::Value> {
+        crate::testing::eval(&self.expr, scope)
+    }
+}
+
+impl<T, E> ProjectFrom for Computed<T, E>
+where
+    T: Table,
+    E: Lower<T::DB> + Build<T::DB>,
+{
+    type Table = T;
+}
+
+#[async_trait::async_trait]
+impl<T, E> ProjectAndProbe<T::DB> for Computed<T, E>
 where
-    DB: Database,
     T: Table,
-    Out: Sized + Send + Sync + 'static,
+    E: Lower<T::DB> + Build<T::DB>,
 {
-    fn project_and_probe(self, probing: &Probing<DB>) -> UrmResult<()> {
+    async fn project_and_probe(self, probing: &Probing<T::DB>) -> UrmResult<()> {
         probing
             .select()
             .projection
             .lock()
-            .insert(self.local_id, QueryField::Primitive);
+            .insert(self.local_id.into(), QueryField::Primitive(Box::new(self)));
         Ok(())
     }
 }
+
+impl<T, E> ProjectAndDeserialize<T::DB> for Computed<T, E>
+where
+    T: Table + Instance,
+    E: Lower<T::DB> + Build<T::DB>,
+    <E::Ty as Type>::Output: serde::de::DeserializeOwned,
+{
+    fn project_and_deserialize(
+        self,
+        row: &serde_json::Value,
+    ) -> UrmResult<<E::Ty as Type>::Output> {
+        deserialize_value::<T::DB, E::Ty>(
+            T::instance().name(),
+            self.name,
+            &self.local_id.into(),
+            row,
+        )
+    }
+}
+
+///
+/// An expression over the columns of the table `T`, projected under a key of its own.
+///
+/// Unlike `Computed`, the expression is not declared in `#[urm::table]`,
+/// so any `Lower + Build` expression may be projected ad hoc, see `expression`.
+///
+pub struct Expression<T, E> {
+    expr: E,
+    table: std::marker::PhantomData<T>,
+}
+
+/// Project an expression, e.g. `urm::project(self, column::expression(Equals(..)))`.
+///
+/// The table is inferred from where the expression is projected.
+pub fn expression<T, E>(expr: E) -> Expression<T, E>
+where
+    T: Table,
+    E: Lower<T::DB> + Build<T::DB>,
+{
+    Expression {
+        expr,
+        table: std::marker::PhantomData,
+    }
+}
+
+impl<T, E> Expression<T, E>
+where
+    T: Table + Instance,
+    E: Lower<T::DB> + Build<T::DB>,
+{
+    /// The key of this projection, a hash of the expression's SQL with arguments inlined,
+    /// so that equal expressions share a key and different ones don't.
+    fn projection_key(&self) -> ProjectionKey {
+        let mut query = Query::new_inlined();
+        let table = TableAlias {
+            table: T::instance(),
+            alias: 0,
+        };
+        self.expr.build(&mut QueryBuilder::new(table, &mut query));
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        query.sql.hash(&mut hasher);
+        ProjectionKey::Expression(hasher.finish())
+    }
+}
+
+impl<T, E: Clone> Clone for Expression<T, E> {
+    fn clone(&self) -> Self {
+        Self {
+            expr: self.expr.clone(),
+            table: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<T, E> Typed<T::DB> for Expression<T, E>
+where
+    T: Table,
+    E: Typed<T::DB>,
+{
+    type Ty = E::Ty;
+}
+
+impl<T, E> ProjectFrom for Expression<T, E>
+where
+    T: Table,
+    E: Lower<T::DB> + Build<T::DB>,
+{
+    type Table = T;
+}
+
+#[async_trait::async_trait]
+impl<T, E> ProjectAndProbe<T::DB> for Expression<T, E>
+where
+    T: Table + Instance,
+    E: Lower<T::DB> + Build<T::DB>,
+{
+    async fn project_and_probe(self, probing: &Probing<T::DB>) -> UrmResult<()> {
+        let projection_key = self.projection_key();
+        probing
+            .select()
+            .projection
+            .lock()
+            .insert(projection_key, QueryField::Primitive(Box::new(self.expr)));
+        Ok(())
+    }
+}
+
+impl<T, E> ProjectAndDeserialize<T::DB> for Expression<T, E>
+where
+    T: Table + Instance,
+    E: Lower<T::DB> + Build<T::DB>,
+    <E::Ty as Type>::Output: serde::de::DeserializeOwned,
+{
+    fn project_and_deserialize(
+        self,
+        row: &serde_json::Value,
+    ) -> UrmResult<<E::Ty as Type>::Output> {
+        let projection_key = self.projection_key();
+        deserialize_value::<T::DB, E::Ty>(
+            T::instance().name(),
+            &projection_key.key(),
+            &projection_key,
+            row,
+        )
+    }
+}
+
+/// Deserialize the value of a projection of `table.column`, having type `Ty`,
+/// where an expression is named by its projection key.
+fn deserialize_value<DB: Database, Ty: Type>(
+    table: &'static str,
+    column: &str,
+    projection_key: &ProjectionKey,
+    row: &serde_json::Value,
+) -> UrmResult<Ty::Output>
+where
+    Ty::Output: serde::de::DeserializeOwned,
+{
+    let value = row
+        .get(projection_key.key())
+        .cloned()
+        .unwrap_or(serde_json::Value::Null);
+
+    let decode_error = |error: serde_json::Error| UrmError::Decode {
+        table,
+        column: column.to_string(),
+        expected: std::any::type_name::<Ty>(),
+        message: error.to_string(),
+    };
+
+    match Ty::decode(value.clone()) {
+        Ok(output) => Ok(output),
+        Err(error) => match (DB::JSON_BOOL_AS_INTEGER, value.as_u64()) {
+            (true, Some(integer @ 0..=1)) => {
+                serde_json::from_value(serde_json::Value::Bool(integer == 1))
+                    .map_err(|_| decode_error(error))
+            }
+            _ => Err(decode_error(error)),
+        },
+    }
+}
@@ -0,0 +1,84 @@
+//!
+//! The SQL syntax of a database.
+//!
+
+use std::fmt::Write;
+
+///
+/// Describes how a database spells the SQL constructs that the query engine produces.
+///
+/// The default implementations follow standard SQL (as spoken by Postgres), so a
+/// backend only needs to override what it spells differently.
+///
+pub trait Dialect {
+    /// SQL function building a JSON object out of key/value pairs.
+    const JSON_OBJECT: &'static str;
+
+    /// SQL aggregate function building a JSON array out of JSON values.
+    const JSON_ARRAY_AGG: &'static str;
+
+    /// SQL function that re-interprets a JSON value read back from a subquery as JSON,
+    /// for databases that represent JSON as text.
+    const JSON_FROM_SUBQUERY: Option<&'static str> = None;
+
+    /// Whether boolean values come back inside JSON objects as the integers `1` and `0`,
+    /// for databases without a boolean type.
+    const JSON_BOOL_AS_INTEGER: bool = false;
+
+    /// Operator dividing two integers, truncating towards zero.
+    const INTEGER_DIVISION: &'static str = "/";
+
+    /// Write a quoted identifier, e.g. a table or column name.
+    fn write_identifier(buf: &mut String, identifier: &str) {
+        write!(buf, "\"{}\"", identifier.replace('"', "\"\"")).unwrap();
+    }
+
+    /// Write the placeholder of the query argument at the given zero-based index.
+    fn write_placeholder(buf: &mut String, _index: usize) {
+        buf.push('?');
+    }
+
+    /// Write the placeholder of an enum argument, which is bound as the text of its label.
+    fn write_enum_placeholder(buf: &mut String, index: usize, _type_name: &str) {
+        Self::write_placeholder(buf, index);
+    }
+
+    /// Write the clause limiting the rows of a select.
+    /// Is only called when there is either a limit or a non-zero offset.
+    fn write_limit_offset(buf: &mut String, limit: Option<usize>, offset: usize) {
+        match (limit, offset) {
+            (Some(limit), 0) => write!(buf, "LIMIT {}", limit),
+            (Some(limit), offset) => write!(buf, "LIMIT {} OFFSET {}", limit, offset),
+            (None, offset) => write!(buf, "OFFSET {}", offset),
+        }
+        .unwrap();
+    }
+
+    /// Write a boolean literal.
+    fn write_bool(buf: &mut String, value: bool) {
+        buf.push_str(if value { "TRUE" } else { "FALSE" });
+    }
+
+    /// Write an argument value as an SQL literal, used when rendering queries with inlined arguments.
+    fn write_literal(buf: &mut String, value: &serde_json::Value) {
+        use serde_json::Value;
+
+        match value {
+            Value::Null => buf.push_str("NULL"),
+            Value::Bool(value) => Self::write_bool(buf, *value),
+            Value::Number(number) => write!(buf, "{}", number).unwrap(),
+            Value::String(string) => write!(buf, "'{}'", string.replace('\'', "''")).unwrap(),
+            Value::Array(values) => {
+                buf.push_str("ARRAY[");
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        buf.push_str(", ");
+                    }
+                    Self::write_literal(buf, value);
+                }
+                buf.push(']');
+            }
+            Value::Object(_) => write!(buf, "'{}'", value.to_string().replace('\'', "''")).unwrap(),
+        }
+    }
+}
@@ -1,7 +1,157 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use crate::builder::Query;
+use crate::UrmResult;
+
+///
+/// Implement a `Database` executed through its `sqlx` driver,
+/// which is the same for every backend apart from the driver types.
+///
+/// Each row of a query is a single JSON column, decoded into a `serde_json::Value`.
+///
+#[cfg(any(feature = "postgres", feature = "sqlite", feature = "mysql"))]
+macro_rules! sqlx_database {
+    ($db:ident, $sqlx:ty, $arguments:ty) => {
+        impl $crate::database::Database for $db {
+            type Arguments = $arguments;
+        }
+
+        impl<T> $crate::builder::Argument<$db> for T
+        where
+            T: sqlx::Encode<'static, $sqlx>
+                + sqlx::Type<$sqlx>
+                + serde::Serialize
+                + Clone
+                + Send
+                + Sync
+                + 'static,
+        {
+            fn bind(&self, arguments: &mut $arguments) {
+                use sqlx::Arguments;
+                arguments.add(self.clone());
+            }
+
+            fn to_value(&self) -> serde_json::Value {
+                serde_json::to_value(self).unwrap_or(serde_json::Value::Null)
+            }
+        }
+
+        #[async_trait::async_trait]
+        impl $crate::database::Executor<$db> for sqlx::Pool<$sqlx> {
+            async fn fetch_rows(
+                &self,
+                query: $crate::builder::Query<$db>,
+            ) -> $crate::UrmResult<Vec<serde_json::Value>> {
+                let mut arguments = <$arguments>::default();
+                for argument in &query.arguments {
+                    argument.bind(&mut arguments);
+                }
+
+                let rows: Vec<sqlx::types::Json<serde_json::Value>> =
+                    sqlx::query_scalar_with(&query.sql, arguments)
+                        .fetch_all(self)
+                        .await?;
+
+                Ok(rows.into_iter().map(|row| row.0).collect())
+            }
+        }
+
+        impl<T> $crate::ty::Typed<$db> for $crate::value::Scalar<T>
+        where
+            T: $crate::builder::Argument<$db> + Clone,
+        {
+            type Ty = $crate::ty::Unit<T>;
+        }
+
+        impl<T> $crate::lower::Lower<$db> for $crate::value::Scalar<T>
+        where
+            T: $crate::builder::Argument<$db> + Clone,
+        {
+            fn lower(self) -> Option<$crate::lower::Lowered<$db>> {
+                Some($crate::lower::Lowered::Expr(Box::new(self)))
+            }
+        }
+
+        impl<T> $crate::builder::Build<$db> for $crate::value::Scalar<T>
+        where
+            T: $crate::builder::Argument<$db> + Clone,
+        {
+            fn build(&self, builder: &mut $crate::builder::QueryBuilder<$db>) {
+                builder.push_argument(self.0.clone());
+            }
+
+            #[cfg(feature = "testing")]
+            fn as_eval(&self) -> Option<&dyn $crate::testing::Eval<$db>> {
+                Some(self)
+            }
+        }
+    };
+}
+
 #[cfg(feature = "postgres")]
 mod postgres;
 
-pub trait Database: std::fmt::Debug + Sync + Send + Clone + 'static {}
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+#[cfg(feature = "mysql")]
+mod mysql;
+
+mod dialect;
+
+pub use dialect::Dialect;
+
+pub trait Database: Dialect + std::fmt::Debug + Sync + Send + Clone + 'static {
+    /// The driver-level arguments that query arguments are bound into.
+    type Arguments;
+}
+
+///
+/// Something that is able to execute queries on a database, e.g. a connection pool.
+///
+/// Each returned row is a JSON object containing the projection of one root node.
+///
+#[async_trait]
+pub trait Executor<DB: Database>: Send + Sync + 'static {
+    async fn fetch_rows(&self, query: Query<DB>) -> UrmResult<Vec<serde_json::Value>>;
+}
+
+///
+/// A shared handle to an `Executor`.
+///
+/// When using async-graphql, this should be registered as schema data,
+/// which is where root selects look for the database.
+///
+pub struct Connection<DB: Database> {
+    executor: Arc<dyn Executor<DB>>,
+}
+
+impl<DB: Database> Connection<DB> {
+    pub fn new<E: Executor<DB>>(executor: E) -> Self {
+        Self {
+            executor: Arc::new(executor),
+        }
+    }
+
+    pub async fn fetch_rows(&self, query: Query<DB>) -> UrmResult<Vec<serde_json::Value>> {
+        self.executor.fetch_rows(query).await
+    }
+}
+
+impl<DB: Database> Clone for Connection<DB> {
+    fn clone(&self) -> Self {
+        Self {
+            executor: self.executor.clone(),
+        }
+    }
+}
 
 #[cfg(feature = "postgres")]
 pub use postgres::Postgres;
+
+#[cfg(feature = "sqlite")]
+pub use sqlite::Sqlite;
+
+#[cfg(feature = "mysql")]
+pub use mysql::MySql;
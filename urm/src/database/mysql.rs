@@ -0,0 +1,87 @@
+use super::Dialect;
+use crate::builder::{Argument, Build, QueryBuilder};
+use crate::lower::{Lower, Lowered};
+use crate::ty;
+use crate::value::Vector;
+
+#[derive(Clone, Debug)]
+pub struct MySql;
+
+sqlx_database!(MySql, sqlx::MySql, sqlx::mysql::MySqlArguments);
+
+impl Dialect for MySql {
+    const JSON_OBJECT: &'static str = "JSON_OBJECT";
+    const JSON_ARRAY_AGG: &'static str = "JSON_ARRAYAGG";
+    const JSON_BOOL_AS_INTEGER: bool = true;
+
+    /// In MySQL, `/` of two integers yields a decimal.
+    const INTEGER_DIVISION: &'static str = "DIV";
+
+    fn write_identifier(buf: &mut String, identifier: &str) {
+        use std::fmt::Write;
+        write!(buf, "`{}`", identifier.replace('`', "``")).unwrap();
+    }
+
+    /// MySQL can't OFFSET without a LIMIT, so the limit is the largest possible row count.
+    fn write_limit_offset(buf: &mut String, limit: Option<usize>, offset: usize) {
+        use std::fmt::Write;
+        match (limit, offset) {
+            (Some(limit), 0) => write!(buf, "LIMIT {}", limit),
+            (Some(limit), offset) => write!(buf, "LIMIT {} OFFSET {}", limit, offset),
+            (None, offset) => write!(buf, "LIMIT 18446744073709551615 OFFSET {}", offset),
+        }
+        .unwrap();
+    }
+}
+
+/// MySQL has no array type, so vectors are expanded into
+/// a comma-separated list of bound arguments, as used by `IN (..)`.
+/// An empty vector is written as `NULL`, which matches nothing.
+impl<T> ty::Typed<MySql> for Vector<T>
+where
+    T: Argument<MySql> + Clone,
+{
+    type Ty = ty::Vector<T>;
+}
+
+impl<T> Lower<MySql> for Vector<T>
+where
+    T: Argument<MySql> + Clone,
+{
+    fn lower(self) -> Option<Lowered<MySql>> {
+        Some(Lowered::Expr(Box::new(self)))
+    }
+}
+
+impl<T> Build<MySql> for Vector<T>
+where
+    T: Argument<MySql> + Clone,
+{
+    fn build(&self, builder: &mut QueryBuilder<MySql>) {
+        if self.0.is_empty() {
+            builder.push("NULL");
+        }
+
+        for (index, item) in self.0.iter().enumerate() {
+            if index > 0 {
+                builder.push(", ");
+            }
+            builder.push_argument(item.clone());
+        }
+    }
+
+    #[cfg(feature = "testing")]
+    fn as_eval(&self) -> Option<&dyn crate::testing::Eval<MySql>> {
+        Some(self)
+    }
+}
+
+#[cfg(feature = "testing")]
+impl<T> crate::testing::Eval<MySql> for Vector<T>
+where
+    T: Argument<MySql> + Clone,
+{
+    fn eval(&self, _scope: &crate::testing::Scope) -> crate::UrmResult<serde_json::Value> {
+        Ok(self.0.iter().map(Argument::to_value).collect())
+    }
+}
@@ -1,49 +1,51 @@
-use super::Database;
-use crate::builder::{Build, QueryBuilder};
+use super::Dialect;
+use crate::builder::{Argument, Build, QueryBuilder};
 use crate::lower::{Lower, Lowered};
 use crate::ty;
-use crate::value::{Scalar, Vector};
+use crate::value::Vector;
 
 #[derive(Clone, Debug)]
 pub struct Postgres;
 
-impl Database for Postgres {}
+sqlx_database!(Postgres, sqlx::Postgres, sqlx::postgres::PgArguments);
 
-impl<T> ty::Typed<Postgres> for Scalar<T>
-where
-    T: sqlx::Type<sqlx::Postgres> + Send + Sync + 'static,
-{
-    type Ty = ty::Unit<T>;
-}
+impl Dialect for Postgres {
+    const JSON_OBJECT: &'static str = "jsonb_build_object";
+    const JSON_ARRAY_AGG: &'static str = "jsonb_agg";
 
-impl<T> Lower<Postgres> for Scalar<T>
-where
-    T: sqlx::Type<sqlx::Postgres> + Send + Sync + 'static,
-{
-    fn lower(self) -> Option<Lowered<Postgres>> {
-        Some(Lowered::Expr(Box::new(self)))
+    fn write_placeholder(buf: &mut String, index: usize) {
+        use std::fmt::Write;
+        write!(buf, "${}", index + 1).unwrap();
     }
-}
 
-impl<T> Build<Postgres> for Scalar<T>
-where
-    T: sqlx::Type<sqlx::Postgres> + Send + Sync + 'static,
-{
-    fn build(&self, builder: &mut QueryBuilder<Postgres>) {
-        builder.push("?");
+    /// Text isn't implicitly converted to an enum type, so the label is cast.
+    /// The type name is quoted like any identifier, part by part if it is schema-qualified.
+    fn write_enum_placeholder(buf: &mut String, index: usize, type_name: &str) {
+        buf.push_str("CAST(");
+        Self::write_placeholder(buf, index);
+        buf.push_str(" AS ");
+        for (position, part) in type_name.split('.').enumerate() {
+            if position > 0 {
+                buf.push('.');
+            }
+            Self::write_identifier(buf, part);
+        }
+        buf.push(')');
     }
 }
 
 impl<T> ty::Typed<Postgres> for Vector<T>
 where
-    T: sqlx::Type<sqlx::Postgres> + Send + Sync + 'static,
+    T: Clone + Send + Sync + 'static,
+    Vec<T>: Argument<Postgres>,
 {
     type Ty = ty::Vector<T>;
 }
 
 impl<T> Lower<Postgres> for Vector<T>
 where
-    T: sqlx::Type<sqlx::Postgres> + Send + Sync + 'static,
+    T: Clone + Send + Sync + 'static,
+    Vec<T>: Argument<Postgres>,
 {
     fn lower(self) -> Option<Lowered<Postgres>> {
         Some(Lowered::Expr(Box::new(self)))
@@ -52,9 +54,26 @@ where
 
 impl<T> Build<Postgres> for Vector<T>
 where
-    T: sqlx::Type<sqlx::Postgres> + Send + Sync + 'static,
+    T: Clone + Send + Sync + 'static,
+    Vec<T>: Argument<Postgres>,
 {
     fn build(&self, builder: &mut QueryBuilder<Postgres>) {
-        builder.push("?");
+        builder.push_argument(self.0.clone());
+    }
+
+    #[cfg(feature = "testing")]
+    fn as_eval(&self) -> Option<&dyn crate::testing::Eval<Postgres>> {
+        Some(self)
+    }
+}
+
+#[cfg(feature = "testing")]
+impl<T> crate::testing::Eval<Postgres> for Vector<T>
+where
+    T: Clone + Send + Sync + 'static,
+    Vec<T>: Argument<Postgres>,
+{
+    fn eval(&self, _scope: &crate::testing::Scope) -> crate::UrmResult<serde_json::Value> {
+        Ok(self.0.to_value())
     }
 }
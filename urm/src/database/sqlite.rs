@@ -0,0 +1,70 @@
+use super::Dialect;
+use crate::builder::{Build, QueryBuilder};
+use crate::lower::{Lower, Lowered};
+use crate::ty;
+use crate::value::Vector;
+
+#[derive(Clone, Debug)]
+pub struct Sqlite;
+
+sqlx_database!(Sqlite, sqlx::Sqlite, sqlx::sqlite::SqliteArguments<'static>);
+
+impl Dialect for Sqlite {
+    const JSON_OBJECT: &'static str = "json_object";
+    const JSON_ARRAY_AGG: &'static str = "json_group_array";
+    const JSON_FROM_SUBQUERY: Option<&'static str> = Some("json");
+    const JSON_BOOL_AS_INTEGER: bool = true;
+
+    /// SQLite can't OFFSET without a LIMIT, where a negative limit means no limit.
+    fn write_limit_offset(buf: &mut String, limit: Option<usize>, offset: usize) {
+        use std::fmt::Write;
+        match (limit, offset) {
+            (Some(limit), 0) => write!(buf, "LIMIT {}", limit),
+            (Some(limit), offset) => write!(buf, "LIMIT {} OFFSET {}", limit, offset),
+            (None, offset) => write!(buf, "LIMIT -1 OFFSET {}", offset),
+        }
+        .unwrap();
+    }
+}
+
+/// SQLite has no array type, so vectors are bound as JSON text,
+/// to be unpacked using `json_each`.
+impl<T> ty::Typed<Sqlite> for Vector<T>
+where
+    T: serde::Serialize + Send + Sync + 'static,
+{
+    type Ty = ty::Vector<T>;
+}
+
+impl<T> Lower<Sqlite> for Vector<T>
+where
+    T: serde::Serialize + Send + Sync + 'static,
+{
+    fn lower(self) -> Option<Lowered<Sqlite>> {
+        Some(Lowered::Expr(Box::new(self)))
+    }
+}
+
+impl<T> Build<Sqlite> for Vector<T>
+where
+    T: serde::Serialize + Send + Sync + 'static,
+{
+    fn build(&self, builder: &mut QueryBuilder<Sqlite>) {
+        builder.push_argument(serde_json::to_string(&self.0).unwrap());
+    }
+
+    #[cfg(feature = "testing")]
+    fn as_eval(&self) -> Option<&dyn crate::testing::Eval<Sqlite>> {
+        Some(self)
+    }
+}
+
+#[cfg(feature = "testing")]
+impl<T> crate::testing::Eval<Sqlite> for Vector<T>
+where
+    T: serde::Serialize + Send + Sync + 'static,
+{
+    fn eval(&self, _scope: &crate::testing::Scope) -> crate::UrmResult<serde_json::Value> {
+        serde_json::to_value(&self.0).map_err(|error| crate::UrmError::Eval(error.to_string()))
+    }
+}
@@ -1,14 +1,17 @@
 use parking_lot::Mutex;
 use std::collections::BTreeMap;
 use std::fmt::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
 use crate::builder;
-use crate::builder::{Build, QueryBuilder};
+use crate::builder::{Build, Query, QueryBuilder};
 use crate::database::Database;
 use crate::expr;
-use crate::lower::Lowered;
+use crate::inspect;
+use crate::lower::{BuildRange, Lowered};
 use crate::project;
+use crate::quantify::Quantity;
 use crate::Table;
 
 #[derive(Clone)]
@@ -20,22 +23,25 @@ impl<DB: Database> Engine<DB> {
     pub fn new_select(
         from: &'static dyn Table<DB = DB>,
         filter: Option<Lowered<DB>>,
+        range: Box<dyn BuildRange<DB>>,
     ) -> (Self, Probing<DB>) {
-        let root_select = Arc::new(Select {
-            from: expr::TableAlias {
+        let root_select = Arc::new(Select::new(
+            expr::TableAlias {
                 table: from,
                 alias: 0,
             },
-            projection: Mutex::new(BTreeMap::new()),
             filter,
-        });
+            range,
+        ));
 
         let query_engine = Arc::new(Mutex::new(QueryEngine {
             root_select: root_select.clone(),
+            next_alias: 1,
+            probe_failures: vec![],
         }));
 
         let engine = Self {
-            query: query_engine.clone(),
+            query: query_engine,
         };
 
         (engine.clone(), Probing::new(engine, root_select))
@@ -45,32 +51,58 @@ impl<DB: Database> Engine<DB> {
 #[derive(Debug)]
 pub struct QueryEngine<DB: Database> {
     root_select: Arc<Select<DB>>,
+    next_alias: u16,
+
+    /// Failures from probing nested selects, reported by the root select.
+    pub probe_failures: Vec<crate::probe::ProbeFailure>,
 }
 
 impl<DB: Database> QueryEngine<DB> {
     pub fn new_select(
-        &self,
+        &mut self,
         from: &'static dyn Table<DB = DB>,
         filter: Option<Lowered<DB>>,
+        range: Box<dyn BuildRange<DB>>,
     ) -> Arc<Select<DB>> {
-        Arc::new(Select {
-            from: expr::TableAlias {
-                table: from,
-                alias: 0,
-            },
-            projection: Mutex::new(BTreeMap::new()),
+        let alias = self.next_alias;
+        self.next_alias += 1;
+
+        Arc::new(Select::new(
+            expr::TableAlias { table: from, alias },
             filter,
-        })
+            range,
+        ))
+    }
+
+    pub fn build_query(&self) -> Query<DB> {
+        let mut query = Query::new();
+        let mut builder = QueryBuilder::new(self.root_select.from.clone(), &mut query);
+
+        self.root_select.build_rows(&mut builder, None);
+        query.select = Some(self.root_select.clone());
+
+        query
     }
 
-    pub fn build_query(&self, builder: &mut QueryBuilder<DB>) {
-        self.root_select.build_query(builder);
+    pub fn inspect(&self) -> inspect::Inspection {
+        let query = self.build_query();
+
+        let mut inlined = Query::new_inlined();
+        self.root_select.build_rows(
+            &mut QueryBuilder::new(self.root_select.from.clone(), &mut inlined),
+            None,
+        );
+
+        inspect::Inspection {
+            select: self.root_select.inspect(None),
+            arguments: query.argument_values(),
+            sql: query.sql,
+            inlined_sql: inlined.sql,
+        }
     }
 }
 
-/// Each Probing the QueryEngine
-/// hands out must be completed by
-/// calling the `complete` method.
+/// A select being probed, along with the engine building its query.
 #[derive(Clone)]
 pub struct Probing<DB: Database> {
     engine: Engine<DB>,
@@ -89,6 +121,17 @@ impl<DB: Database> Probing<DB> {
     pub fn select(&self) -> &Arc<Select<DB>> {
         &self.select
     }
+
+    /// The number of resolvers parked at `urm::project` on this select so far.
+    pub fn parked(&self) -> usize {
+        self.select.parked.load(Ordering::SeqCst)
+    }
+
+    /// Park a resolver that has finished projecting. The returned future never resolves.
+    pub async fn park<T>(&self) -> T {
+        self.select.parked.fetch_add(1, Ordering::SeqCst);
+        crate::never::never().await
+    }
 }
 
 /// # Select
@@ -104,63 +147,183 @@ pub struct Select<DB: Database> {
     /// The projection, which is getting built dynamically. Eh...
     /// TODO: Does the projection contain all child "queries"?
     /// not likely.
-    pub projection: Mutex<BTreeMap<project::LocalId, QueryField<DB>>>,
+    pub projection: Mutex<BTreeMap<project::ProjectionKey, QueryField<DB>>>,
 
     pub filter: Option<Lowered<DB>>,
+
+    pub range: Box<dyn BuildRange<DB>>,
+
+    /// The number of resolvers parked at `urm::project` while probing this select.
+    parked: AtomicUsize,
 }
 
 impl<DB: Database> Select<DB> {
-    fn build_query(&self, builder: &mut builder::QueryBuilder<DB>) {
+    fn new(
+        from: expr::TableAlias<DB>,
+        filter: Option<Lowered<DB>>,
+        range: Box<dyn BuildRange<DB>>,
+    ) -> Self {
+        Self {
+            from,
+            projection: Mutex::new(BTreeMap::new()),
+            filter,
+            range,
+            parked: AtomicUsize::new(0),
+        }
+    }
+
+    /// Build a select producing one JSON object per row,
+    /// optionally naming the JSON column.
+    fn build_rows(&self, builder: &mut builder::QueryBuilder<DB>, column_alias: Option<&str>) {
         builder.push("SELECT");
         builder.newline_indent();
 
-        {
-            // TODO: db-dependent 'syntax'
-            builder.push("jsonb_build_object(");
-
-            builder.newline_indent();
-            for (local_id, query_field) in self.projection.lock().iter() {
-                write!(builder.buf_mut(), "'{}', ", local_id.0).unwrap();
-                match query_field {
-                    QueryField::Primitive => builder.push("PRIMITIVE,"),
-                    QueryField::Foreign {
-                        select,
-                        // join_predicate,
-                    } => {
-                        builder.push("(");
-                        builder.newline_indent();
-                        select.build_query(&mut builder.push_table(select.from.table));
-                        builder.newline_outdent();
-                        builder.push(")");
-                    }
-                }
-                builder.newline();
-            }
-            builder.newline_outdent();
+        self.build_json_object(builder);
 
-            builder.push(")");
+        if let Some(column_alias) = column_alias {
+            write!(builder.buf_mut(), " AS {}", column_alias).unwrap();
         }
 
         builder.newline_outdent();
 
-        write!(
-            builder.buf_mut(),
-            "FROM {} a{}",
-            self.from.table.name(),
-            self.from.alias
-        )
-        .unwrap();
+        builder.push("FROM ");
+        builder.push_identifier(self.from.table.name());
+        write!(builder.buf_mut(), " a{}", self.from.alias).unwrap();
 
         if let Some(filter) = &self.filter {
             builder.newline();
             builder.push("WHERE");
             builder.newline_indent();
             filter.build(builder);
-            builder.newline_outdent();
+            builder.outdent();
+        }
+
+        self.range.build_range(builder);
+    }
+
+    fn build_json_object(&self, builder: &mut builder::QueryBuilder<DB>) {
+        builder.push(DB::JSON_OBJECT);
+        builder.push("(");
+        builder.newline_indent();
+
+        for (index, (projection_key, query_field)) in self.projection.lock().iter().enumerate() {
+            if index > 0 {
+                builder.push(",");
+                builder.newline();
+            }
+
+            write!(builder.buf_mut(), "'{}', ", projection_key.key()).unwrap();
+            match query_field {
+                QueryField::Primitive(expr) => expr.build(builder),
+                QueryField::Foreign { select, quantity } => {
+                    select.build_nested(&mut builder.push_table(select.from.clone()), *quantity)
+                }
+            }
+        }
+
+        builder.newline_outdent();
+        builder.push(")");
+    }
+
+    /// Build this select as a subquery producing one JSON value,
+    /// either an object or an array of objects.
+    fn build_nested(&self, builder: &mut builder::QueryBuilder<DB>, quantity: Quantity) {
+        if let Some(json_fn) = DB::JSON_FROM_SUBQUERY {
+            builder.push(json_fn);
+            builder.push("(");
+        }
+
+        builder.push("(");
+        builder.newline_indent();
+
+        match quantity {
+            Quantity::One => self.build_rows(builder, None),
+            Quantity::Many => {
+                let rows_alias = format!("r{}", self.from.alias);
+
+                builder.push("SELECT ");
+                builder.push(DB::JSON_ARRAY_AGG);
+                builder.push("(");
+                match DB::JSON_FROM_SUBQUERY {
+                    Some(json_fn) => write!(builder.buf_mut(), "{}({}.node)", json_fn, rows_alias),
+                    None => write!(builder.buf_mut(), "{}.node", rows_alias),
+                }
+                .unwrap();
+                builder.push(")");
+                builder.newline();
+                builder.push("FROM (");
+                builder.newline_indent();
+                self.build_rows(builder, Some("node"));
+                builder.newline_outdent();
+                write!(builder.buf_mut(), ") {}", rows_alias).unwrap();
+            }
+        }
+
+        builder.newline_outdent();
+        builder.push(")");
+
+        if DB::JSON_FROM_SUBQUERY.is_some() {
+            builder.push(")");
+        }
+    }
+}
+
+impl<DB: Database> Select<DB> {
+    fn inspect(&self, parent: Option<&expr::TableAlias<DB>>) -> inspect::SelectInspection {
+        let projection = self
+            .projection
+            .lock()
+            .iter()
+            .map(|(projection_key, query_field)| match query_field {
+                QueryField::Primitive(expr) => inspect::FieldInspection::Primitive {
+                    key: projection_key.key(),
+                    expr: inspect_fragment(&self.from, parent, |builder| expr.build(builder)),
+                },
+                QueryField::Foreign { select, quantity } => inspect::FieldInspection::Foreign {
+                    key: projection_key.key(),
+                    quantity: *quantity,
+                    select: Box::new(select.inspect(Some(&self.from))),
+                },
+            })
+            .collect();
+
+        let (limit, offset) = self.range.limit_offset();
+
+        inspect::SelectInspection {
+            table: self.from.table.name().to_string(),
+            alias: format!("a{}", self.from.alias),
+            projection,
+            filter: self.filter.as_ref().map(|filter| {
+                inspect_fragment(&self.from, parent, |builder| filter.build(builder))
+            }),
+            limit,
+            offset,
         }
     }
 }
 
+/// Render an expression on its own, in the scope of the given tables.
+fn inspect_fragment<DB: Database>(
+    table: &expr::TableAlias<DB>,
+    parent_table: Option<&expr::TableAlias<DB>>,
+    build: impl Fn(&mut QueryBuilder<DB>),
+) -> inspect::Fragment {
+    let render = |mut query: Query<DB>| {
+        let mut builder = QueryBuilder::new(table.clone(), &mut query);
+        builder.parent_table = parent_table.cloned();
+        build(&mut builder);
+        query
+    };
+
+    let query = render(Query::new());
+
+    inspect::Fragment {
+        arguments: query.argument_values(),
+        sql: query.sql,
+        inlined_sql: render(Query::new_inlined()).sql,
+    }
+}
+
 impl<DB: Database> std::fmt::Debug for Select<DB> {
     fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
         let lock = self.projection.lock();
@@ -169,11 +332,22 @@ impl<DB: Database> std::fmt::Debug for Select<DB> {
     }
 }
 
-#[derive(Debug)]
 pub enum QueryField<DB: Database> {
-    Primitive,
+    Primitive(Box<dyn Build<DB>>),
     Foreign {
         select: Arc<Select<DB>>,
+        quantity: Quantity,
         // join_predicate: Box<dyn BuildPredicate<DB>>,
     },
 }
+
+impl<DB: Database> std::fmt::Debug for QueryField<DB> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Primitive(_) => write!(fmt, "Primitive"),
+            Self::Foreign { select, quantity } => {
+                write!(fmt, "Foreign({:?}, {:?})", select, quantity)
+            }
+        }
+    }
+}
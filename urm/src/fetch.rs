@@ -0,0 +1,75 @@
+//!
+//! Querying without probing: select → project → fetch.
+//!
+//! ```ignore
+//! let publications: Vec<(String, Vec<String>)> = urm::select::<db::Publication>()
+//!     .project((db::Publication.title(), db::Publication.editions().project(db::Edition.id())))
+//!     .fetch(&connection)
+//!     .await?;
+//! ```
+//!
+
+use futures_util::FutureExt;
+
+use crate::database::Connection;
+use crate::engine::Engine;
+use crate::lower::{Lower, LowerWhere};
+use crate::quantify::Quantify;
+use crate::ty::ScalarTyped;
+use crate::{Instance, Node, ProjectNode, Select, Table, UrmError, UrmResult};
+
+///
+/// A select projected with `M`, ready to be fetched.
+///
+pub struct Projected<T: Table, W, Q, M> {
+    pub(crate) select: Select<T, W, Q>,
+    pub(crate) projection: M,
+}
+
+impl<T, W, Q, M> Projected<T, W, Q, M>
+where
+    T: Table + Instance,
+    W: Lower<T::DB> + ScalarTyped<T::DB, bool>,
+    Q: Quantify<M::Output>,
+    M: ProjectNode<T> + Clone,
+{
+    /// Execute the query on `connection`, projecting each row into `M::Output`.
+    pub async fn fetch(self, connection: &Connection<T::DB>) -> UrmResult<Q::Output> {
+        let Projected { select, projection } = self;
+        let table = T::instance();
+        let (engine, probing) =
+            Engine::new_select(table, select.filter.lower_where(), select.range);
+        let node = Node::<T>::new_probe(probing.clone());
+
+        let probe = projection.clone();
+        let failure = crate::probe::probe_field(&probing, table.name().to_string(), async {
+            probe
+                .project_node(&node)
+                .await
+                .map(|_| ())
+                .map_err(|error| error.to_string())
+        })
+        .await;
+
+        let rows = crate::execute(
+            &engine,
+            failure.into_iter().collect(),
+            Some(connection),
+            None,
+        )
+        .await?;
+
+        let units = rows
+            .into_iter()
+            .map(|row| {
+                projection
+                    .clone()
+                    .project_node(&Node::new_deserialize(row))
+                    .now_or_never()
+                    .unwrap_or(Err(UrmError::NotReady))
+            })
+            .collect::<UrmResult<Vec<_>>>()?;
+
+        Q::quantify(units).map_err(|rows| UrmError::quantity::<Q>(table.name(), rows))
+    }
+}
@@ -2,17 +2,20 @@
 //! Foreign projection.
 //!
 
+use futures_util::FutureExt;
+
 use crate::database::Database;
 use crate::engine::{Probing, QueryField};
 use crate::filter;
 use crate::logic::And;
 use crate::lower::{BuildRange, Lower, LowerWhere};
 use crate::predicate::{IntoPredicates, Predicates};
-use crate::project::{LocalId, ProjectAndProbe, ProjectFrom};
+use crate::probe::ProbeDriver;
+use crate::project::{LocalId, ProjectAndDeserialize, ProjectAndProbe, ProjectFrom, ProjectionKey};
 use crate::quantify;
 use crate::quantify::Quantify;
 use crate::ty::{MapTo, ScalarTyped, Type, Typed};
-use crate::{Instance, Node, Probe, Table, UrmResult};
+use crate::{Instance, Node, Probe, ProjectNode, Table, UrmError, UrmResult};
 
 pub trait ProjectForeign:
     ProjectFrom
@@ -21,6 +24,9 @@ pub trait ProjectForeign:
 {
     type ForeignTable: Table + Instance;
 
+    /// The id of this projection within the source table.
+    fn local_id(&self) -> LocalId;
+
     ///
     /// Probe this Foreign, effectively mapping the original
     /// type to the probe-able type `P`.
@@ -30,15 +36,64 @@ pub trait ProjectForeign:
         self,
         func: F,
         ctx: &'c ::async_graphql::context::Context<'_>,
-    ) -> probe_async_graphql::ForeignProbe<'c, Self, F, P>
+    ) -> ForeignProbe<Self, F, P, crate::probe::async_graphql::ContextDriver<'c>>
     where
         Self::Ty: Type<Unit = Node<Self::ForeignTable>> + MapTo<P>,
         F: Fn(<Self::Ty as Type>::Unit) -> P,
         P: async_graphql::ContainerType,
     {
-        let map_to_probe = MapToProbe::new(func);
+        self.probe_with_driver(func, crate::probe::async_graphql::ContextDriver::new(ctx))
+    }
+
+    ///
+    /// Like `probe_with`, but for juniper, probing the fields selected on `P`
+    /// by running their juniper resolvers.
+    ///
+    #[cfg(feature = "juniper")]
+    fn probe_with_executor<'e, 'a, F, P, CtxT, S>(
+        self,
+        func: F,
+        executor: &'e ::juniper::Executor<'a, CtxT, S>,
+    ) -> ForeignProbe<Self, F, P, crate::probe::juniper::ExecutorDriver<'e, 'a, CtxT, S>>
+    where
+        Self::Ty: Type<Unit = Node<Self::ForeignTable>> + MapTo<P>,
+        F: Fn(<Self::Ty as Type>::Unit) -> P,
+        P: ::juniper::GraphQLType<S, Context = CtxT, TypeInfo = ()> + Sync,
+        CtxT: AsRef<crate::probe::Data> + Sync,
+        S: ::juniper::ScalarValue + Send + Sync,
+        for<'b> &'b S: ::juniper::ScalarRefValue<'b>,
+    {
+        self.probe_with_driver(func, crate::probe::juniper::ExecutorDriver::new(executor))
+    }
+
+    ///
+    /// Project the foreign table with `projection`, for querying without probing.
+    ///
+    fn project<M>(self, projection: M) -> ForeignProjection<Self, M>
+    where
+        M: ProjectNode<Self::ForeignTable>,
+    {
+        ForeignProjection {
+            project_foreign: self,
+            projection,
+        }
+    }
 
-        probe_async_graphql::ForeignProbe::new(self, map_to_probe, ctx)
+    ///
+    /// Like `probe_with`, but walking the fields selected on `P` using any `probe::ProbeDriver`.
+    ///
+    fn probe_with_driver<F, P, D>(self, func: F, driver: D) -> ForeignProbe<Self, F, P, D>
+    where
+        Self::Ty: Type<Unit = Node<Self::ForeignTable>> + MapTo<P>,
+        F: Fn(<Self::Ty as Type>::Unit) -> P,
+        P: Sync,
+        D: ProbeDriver<P>,
+    {
+        ForeignProbe {
+            project_foreign: self,
+            map_to_probe: MapToProbe::new(func),
+            driver,
+        }
     }
 }
 
@@ -50,6 +105,7 @@ pub trait ProjectForeign:
 /// `Ty` is the original outcome of the mapping (having Unit type `Node<T2>` for probing to work).
 ///
 pub struct Foreign<T1, T2, Ty, W, R> {
+    local_id: LocalId,
     source_table: std::marker::PhantomData<T1>,
     foreign_table: std::marker::PhantomData<T2>,
     ty: std::marker::PhantomData<Ty>,
@@ -57,7 +113,7 @@ pub struct Foreign<T1, T2, Ty, W, R> {
     range: R,
 }
 
-pub fn foreign<T1, T2, Ty, W>(filter: W) -> Foreign<T1, T2, Ty, W, ()>
+pub fn foreign<T1, T2, Ty, W>(local_id: LocalId, filter: W) -> Foreign<T1, T2, Ty, W, ()>
 where
     T1: Table,
     T2: Table,
@@ -65,6 +121,7 @@ where
     W: Lower<T1::DB> + ScalarTyped<T1::DB, bool>,
 {
     Foreign {
+        local_id,
         source_table: std::marker::PhantomData,
         foreign_table: std::marker::PhantomData,
         ty: std::marker::PhantomData,
@@ -87,6 +144,7 @@ where
 
     fn filter(self, filter: W2) -> Self::Output {
         Self::Output {
+            local_id: self.local_id,
             source_table: std::marker::PhantomData,
             foreign_table: std::marker::PhantomData,
             ty: std::marker::PhantomData,
@@ -109,6 +167,7 @@ where
 
     fn range(self, range: R2) -> Self::Output {
         Self::Output {
+            local_id: self.local_id,
             source_table: std::marker::PhantomData,
             foreign_table: std::marker::PhantomData,
             ty: std::marker::PhantomData,
@@ -118,6 +177,19 @@ where
     }
 }
 
+impl<T1, T2, Ty, W: Clone, R: Clone> Clone for Foreign<T1, T2, Ty, W, R> {
+    fn clone(&self) -> Self {
+        Self {
+            local_id: self.local_id,
+            source_table: std::marker::PhantomData,
+            foreign_table: std::marker::PhantomData,
+            ty: std::marker::PhantomData,
+            filter: self.filter.clone(),
+            range: self.range.clone(),
+        }
+    }
+}
+
 impl<DB, T1, T2, Ty, W, R> Typed<DB> for Foreign<T1, T2, Ty, W, R>
 where
     DB: Database,
@@ -168,6 +240,10 @@ where
     R: BuildRange<T2::DB>,
 {
     type ForeignTable = T2;
+
+    fn local_id(&self) -> LocalId {
+        self.local_id
+    }
 }
 
 /// A projection outcome where there will always be exactly one value.
@@ -241,114 +317,274 @@ where
     type Output = <In::Quantify as Quantify<Out>>::Output;
 }
 
-#[cfg(feature = "async_graphql")]
-pub mod probe_async_graphql {
-    use super::*;
+/// The unit type of a foreign projection, i.e. the `Node` of the foreign table.
+type ForeignUnit<In> =
+    <<In as Typed<<<In as ProjectForeign>::ForeignTable as Table>::DB>>::Ty as Type>::Unit;
 
-    ///
-    /// A foreign projection mapped into a probe-able `async_graphql::ContainerType`.
-    ///
-    pub struct ForeignProbe<'c, In, F, Out>
-    where
-        In: ProjectForeign,
-        Out: async_graphql::ContainerType,
-    {
-        project_foreign: In,
-        map_to_probe:
-            MapToProbe<<<In as Typed<<In::ForeignTable as Table>::DB>>::Ty as Type>::Unit, F, Out>,
-        ctx: &'c ::async_graphql::context::Context<'c>,
-    }
+///
+/// A foreign projection mapped into a probe-able container `Out`,
+/// whose selected fields are walked by the `probe::ProbeDriver` `D`.
+///
+pub struct ForeignProbe<In, F, Out, D>
+where
+    In: ProjectForeign,
+{
+    project_foreign: In,
+    map_to_probe: MapToProbe<ForeignUnit<In>, F, Out>,
+    driver: D,
+}
 
-    impl<'c, In, F, P> ForeignProbe<'c, In, F, P>
-    where
-        In: ProjectForeign,
-        P: async_graphql::ContainerType,
-    {
-        pub(crate) fn new(
-            project_foreign: In,
-            map_to_probe: MapToProbe<
-                <<In as Typed<<In::ForeignTable as Table>::DB>>::Ty as Type>::Unit,
-                F,
-                P,
-            >,
-            ctx: &'c ::async_graphql::context::Context<'c>,
-        ) -> Self {
-            Self {
-                project_foreign,
-                map_to_probe,
-                ctx,
-            }
+impl<In, F, Out, D> ForeignProbe<In, F, Out, D>
+where
+    In: ProjectForeign,
+    Out: Sync,
+    D: ProbeDriver<Out>,
+{
+    /// The key of this projection, using the alias of the probed field, if any.
+    fn projection_key(&self) -> ProjectionKey {
+        ProjectionKey::Field {
+            local_id: self.project_foreign.local_id(),
+            alias: self.driver.alias().map(ToString::to_string),
         }
     }
+}
 
-    impl<'c, In, F, Out> Typed<<In::ForeignTable as Table>::DB> for ForeignProbe<'c, In, F, Out>
-    where
-        In: ProjectForeign,
-        In::Ty: MapTo<Out>,
-        <<In::Ty as MapTo<Out>>::Quantify as Quantify<Out>>::Output: Send + Sync + 'static,
-        F: Send + Sync + 'static,
-        Out: async_graphql::ContainerType + Send + Sync + 'static,
-    {
-        type Ty = MapToProbe<In::Ty, F, Out>;
+impl<In, F, Out, D> Typed<<In::ForeignTable as Table>::DB> for ForeignProbe<In, F, Out, D>
+where
+    In: ProjectForeign,
+    In::Ty: MapTo<Out>,
+    <<In::Ty as MapTo<Out>>::Quantify as Quantify<Out>>::Output: Send + Sync + 'static,
+    F: Send + Sync + 'static,
+    Out: Send + Sync + 'static,
+    D: ProbeDriver<Out>,
+{
+    type Ty = MapToProbe<In::Ty, F, Out>;
+}
+
+impl<In, F, Out, D> ProjectFrom for ForeignProbe<In, F, Out, D>
+where
+    In: ProjectForeign,
+    In::Ty: MapTo<Out>,
+    <<In::Ty as MapTo<Out>>::Quantify as Quantify<Out>>::Output: Send + Sync + 'static,
+    F: Send + Sync + 'static,
+    Out: Send + Sync + 'static,
+    D: ProbeDriver<Out>,
+{
+    type Table = In::Table;
+}
+
+#[async_trait::async_trait]
+impl<In, F, Out, D> ProjectAndProbe<<In::ForeignTable as Table>::DB> for ForeignProbe<In, F, Out, D>
+where
+    In: ProjectForeign,
+    In::Ty: Type<Unit = Node<In::ForeignTable>> + MapTo<Out>,
+    <<In::Ty as MapTo<Out>>::Quantify as Quantify<Out>>::Output: Send + Sync + 'static,
+    F: (Fn(<In::Ty as Type>::Unit) -> Out) + Send + Sync + 'static,
+    Out: Probe + Send + Sync + 'static,
+    D: ProbeDriver<Out>,
+{
+    async fn project_and_probe(
+        self,
+        probing: &Probing<<In::ForeignTable as Table>::DB>,
+    ) -> UrmResult<()> {
+        let sub_probing = probe_foreign_select(
+            self.projection_key(),
+            self.project_foreign,
+            <<In::Ty as MapTo<Out>>::Quantify as Quantify<Out>>::QUANTITY,
+            probing,
+        );
+        let sub_node = Node::<In::ForeignTable>::new_probe(sub_probing.clone());
+
+        let container = (self.map_to_probe.func)(sub_node);
+        let failures = self.driver.probe(&container, &sub_probing).await;
+        probing
+            .engine()
+            .query
+            .lock()
+            .probe_failures
+            .extend(failures);
+
+        Ok(())
     }
+}
 
-    impl<'c, In, F, Out> ProjectFrom for ForeignProbe<'c, In, F, Out>
-    where
-        In: ProjectForeign,
-        In::Ty: MapTo<Out>,
-        <<In::Ty as MapTo<Out>>::Quantify as Quantify<Out>>::Output: Send + Sync + 'static,
-        F: Send + Sync + 'static,
-        Out: async_graphql::ContainerType + Send + Sync + 'static,
-    {
-        type Table = In::Table;
+impl<In, F, Out, D> ProjectAndDeserialize<<In::ForeignTable as Table>::DB>
+    for ForeignProbe<In, F, Out, D>
+where
+    In: ProjectForeign,
+    In::Ty: Type<Unit = Node<In::ForeignTable>> + MapTo<Out>,
+    <<In::Ty as MapTo<Out>>::Quantify as Quantify<Out>>::Output: Send + Sync + 'static,
+    F: (Fn(<In::Ty as Type>::Unit) -> Out) + Send + Sync + 'static,
+    Out: Probe + Send + Sync + 'static,
+    D: ProbeDriver<Out>,
+{
+    fn project_and_deserialize(
+        self,
+        row: &serde_json::Value,
+    ) -> UrmResult<<<In::Ty as MapTo<Out>>::Quantify as Quantify<Out>>::Output> {
+        let units = foreign_rows(row, &self.projection_key())
+            .into_iter()
+            .map(|value| (self.map_to_probe.func)(Node::new_deserialize(value)))
+            .collect();
+
+        <<In::Ty as MapTo<Out>>::Quantify as Quantify<Out>>::quantify(units).map_err(|rows| {
+            UrmError::quantity::<<In::Ty as MapTo<Out>>::Quantify>(
+                In::ForeignTable::instance().name(),
+                rows,
+            )
+        })
     }
+}
 
-    impl<'c, In, F, Out> ProjectAndProbe<<In::ForeignTable as Table>::DB>
-        for ForeignProbe<'c, In, F, Out>
-    where
-        In: ProjectForeign,
-        In::Ty: Type<Unit = Node<In::ForeignTable>> + MapTo<Out>,
-        <<In::Ty as MapTo<Out>>::Quantify as Quantify<Out>>::Output: Send + Sync + 'static,
-        F: (Fn(<In::Ty as Type>::Unit) -> Out) + Send + Sync + 'static,
-        Out: Probe + async_graphql::ContainerType + Send + Sync + 'static,
-    {
-        fn project_and_probe(
-            self,
-            probing: &Probing<<In::ForeignTable as Table>::DB>,
-        ) -> UrmResult<()> {
-            let foreign_table = In::ForeignTable::instance();
-            let crate::predicate::Predicates { filter, range } =
-                self.project_foreign.into_predicates();
-
-            let sub_select = probing
-                .engine()
-                .query
-                .lock()
-                .new_select(foreign_table, filter);
-
-            {
-                let mut proj_lock = probing.select().projection.lock();
-
-                proj_lock.insert(
-                    // FIXME: This should be "dynamic" in some way,
-                    // or use a different type of key when projecting.
-                    // perhaps keyed by the predicates..?
-                    LocalId(0),
-                    QueryField::Foreign {
-                        select: sub_select.clone(),
-                    },
-                );
-            }
-
-            let sub_node = Node::<In::ForeignTable>::new_probe(crate::engine::Probing::new(
-                probing.engine().clone(),
-                sub_select,
-            ));
-
-            let container = (self.map_to_probe.func)(sub_node);
-            crate::probe::probe_container(&container, self.ctx);
-
-            Ok(())
-        }
+///
+/// A foreign projection mapped into a nested projection `M` of the foreign table,
+/// for querying without probing.
+///
+#[derive(Clone)]
+pub struct ForeignProjection<In, M> {
+    project_foreign: In,
+    projection: M,
+}
+
+/// The outcome of a foreign projection `In`, mapped into the output `Out` of a nested projection.
+pub struct MapToOutput<In, Out> {
+    outcome: std::marker::PhantomData<In>,
+    output: std::marker::PhantomData<Out>,
+}
+
+impl<In, Out> Type for MapToOutput<In, Out>
+where
+    In: MapTo<Out>,
+    Out: Send + Sync + 'static,
+    <<In as MapTo<Out>>::Quantify as Quantify<Out>>::Output: Send + Sync + 'static,
+{
+    type Unit = Out;
+    type Output = <In::Quantify as Quantify<Out>>::Output;
+}
+
+impl<In, M> Typed<<In::ForeignTable as Table>::DB> for ForeignProjection<In, M>
+where
+    In: ProjectForeign,
+    M: ProjectNode<In::ForeignTable>,
+    In::Ty: MapTo<M::Output>,
+    <<In::Ty as MapTo<M::Output>>::Quantify as Quantify<M::Output>>::Output: Send + Sync + 'static,
+{
+    type Ty = MapToOutput<In::Ty, M::Output>;
+}
+
+impl<In, M> ProjectFrom for ForeignProjection<In, M>
+where
+    In: ProjectForeign,
+    M: ProjectNode<In::ForeignTable>,
+{
+    type Table = In::Table;
+}
+
+#[async_trait::async_trait]
+impl<In, M> ProjectAndProbe<<In::ForeignTable as Table>::DB> for ForeignProjection<In, M>
+where
+    In: ProjectForeign,
+    In::Ty: MapTo<M::Output>,
+    M: ProjectNode<In::ForeignTable>,
+{
+    async fn project_and_probe(
+        self,
+        probing: &Probing<<In::ForeignTable as Table>::DB>,
+    ) -> UrmResult<()> {
+        let sub_probing = probe_foreign_select(
+            self.project_foreign.local_id().into(),
+            self.project_foreign,
+            <<In::Ty as MapTo<M::Output>>::Quantify as Quantify<M::Output>>::QUANTITY,
+            probing,
+        );
+        let sub_node = Node::<In::ForeignTable>::new_probe(sub_probing.clone());
+
+        let projection = self.projection;
+        let failure = crate::probe::probe_field(
+            &sub_probing,
+            In::ForeignTable::instance().name().to_string(),
+            async {
+                projection
+                    .project_node(&sub_node)
+                    .await
+                    .map(|_| ())
+                    .map_err(|error| error.to_string())
+            },
+        )
+        .await;
+        probing.engine().query.lock().probe_failures.extend(failure);
+
+        Ok(())
+    }
+}
+
+impl<In, M> ProjectAndDeserialize<<In::ForeignTable as Table>::DB> for ForeignProjection<In, M>
+where
+    In: ProjectForeign,
+    M: ProjectNode<In::ForeignTable> + Clone,
+    In::Ty: MapTo<M::Output>,
+    <<In::Ty as MapTo<M::Output>>::Quantify as Quantify<M::Output>>::Output: Send + Sync + 'static,
+{
+    fn project_and_deserialize(
+        self,
+        row: &serde_json::Value,
+    ) -> UrmResult<<<In::Ty as MapTo<M::Output>>::Quantify as Quantify<M::Output>>::Output> {
+        let units = foreign_rows(row, &self.project_foreign.local_id().into())
+            .into_iter()
+            .map(|value| {
+                self.projection
+                    .clone()
+                    .project_node(&Node::new_deserialize(value))
+                    .now_or_never()
+                    .unwrap_or(Err(UrmError::NotReady))
+            })
+            .collect::<UrmResult<Vec<_>>>()?;
+
+        <<In::Ty as MapTo<M::Output>>::Quantify as Quantify<M::Output>>::quantify(units).map_err(
+            |rows| {
+                UrmError::quantity::<<In::Ty as MapTo<M::Output>>::Quantify>(
+                    In::ForeignTable::instance().name(),
+                    rows,
+                )
+            },
+        )
+    }
+}
+
+/// Add a select from the foreign table to the select being probed, keyed by `projection_key`,
+/// returning the probing of the new select.
+fn probe_foreign_select<In: ProjectForeign>(
+    projection_key: ProjectionKey,
+    project_foreign: In,
+    quantity: crate::quantify::Quantity,
+    probing: &Probing<<In::ForeignTable as Table>::DB>,
+) -> Probing<<In::ForeignTable as Table>::DB> {
+    let foreign_table = In::ForeignTable::instance();
+    let crate::predicate::Predicates { filter, range } = project_foreign.into_predicates();
+
+    let sub_select =
+        probing
+            .engine()
+            .query
+            .lock()
+            .new_select(foreign_table, filter, Box::new(range));
+
+    probing.select().projection.lock().insert(
+        projection_key,
+        QueryField::Foreign {
+            select: sub_select.clone(),
+            quantity,
+        },
+    );
+
+    Probing::new(probing.engine().clone(), sub_select)
+}
+
+/// The JSON values of the rows of a foreign projection within a row.
+fn foreign_rows(row: &serde_json::Value, projection_key: &ProjectionKey) -> Vec<serde_json::Value> {
+    match row.get(projection_key.key()) {
+        Some(serde_json::Value::Array(values)) => values.clone(),
+        Some(serde_json::Value::Null) | None => vec![],
+        Some(value) => vec![value.clone()],
     }
 }
@@ -0,0 +1,291 @@
+use crate::builder::{Build, QueryBuilder};
+use crate::column::{Column, Computed};
+use crate::database::Database;
+use crate::lower::{Lower, Lowered};
+use crate::ty::{JoinNullable, Numeric, ScalarType, ScalarTyped, Type, Typed};
+use crate::value::Scalar;
+
+/// Define a binary arithmetic operator over two operands having the same `Numeric` unit,
+/// which is null if either operand is.
+macro_rules! binary_operator {
+    ($(#[$meta:meta])* $ident:ident, $sql:literal, $int_op:expr, $float_op:expr) => {
+        $(#[$meta])*
+        #[derive(Clone)]
+        pub struct $ident<L, R>(pub L, pub R);
+
+        impl<DB, L, R> Typed<DB> for $ident<L, R>
+        where
+            DB: Database,
+            L: Lower<DB> + Build<DB>,
+            R: Lower<DB> + Build<DB> + ScalarTyped<DB, <L::Ty as Type>::Unit>,
+            L::Ty: ScalarType + JoinNullable<R::Ty, <L::Ty as Type>::Unit>,
+            <L::Ty as Type>::Unit: Numeric,
+        {
+            type Ty = <L::Ty as JoinNullable<R::Ty, <L::Ty as Type>::Unit>>::Ty;
+        }
+
+        impl<DB, L, R> Lower<DB> for $ident<L, R>
+        where
+            DB: Database,
+            L: Lower<DB> + Build<DB>,
+            R: Lower<DB> + Build<DB> + ScalarTyped<DB, <L::Ty as Type>::Unit>,
+            L::Ty: ScalarType + JoinNullable<R::Ty, <L::Ty as Type>::Unit>,
+            <L::Ty as Type>::Unit: Numeric,
+        {
+            fn lower(self) -> Option<Lowered<DB>> {
+                Some(Lowered::Expr(Box::new(self)))
+            }
+        }
+
+        impl<DB, L, R> Build<DB> for $ident<L, R>
+        where
+            DB: Database,
+            L: Lower<DB> + Build<DB>,
+            R: Lower<DB> + Build<DB> + ScalarTyped<DB, <L::Ty as Type>::Unit>,
+            L::Ty: ScalarType + JoinNullable<R::Ty, <L::Ty as Type>::Unit>,
+            <L::Ty as Type>::Unit: Numeric,
+        {
+            fn build(&self, builder: &mut QueryBuilder<DB>) {
+                builder.push("(");
+                self.0.build(builder);
+                builder.push(" ");
+                builder.push(operator::<DB, <L::Ty as Type>::Unit>($sql));
+                builder.push(" ");
+                self.1.build(builder);
+                builder.push(")");
+            }
+
+            #[cfg(feature = "testing")]
+            fn as_eval(&self) -> Option<&dyn crate::testing::Eval<DB>> {
+                Some(self)
+            }
+        }
+
+        #[cfg(feature = "testing")]
+        impl<DB, L, R> crate::testing::Eval<DB> for $ident<L, R>
+        where
+            DB: Database,
+            L: Typed<DB> + Build<DB>,
+            R: Build<DB>,
+            <L::Ty as Type>::Unit: Numeric,
+        {
+            fn eval(
+                &self,
+                scope: &crate::testing::Scope,
+            ) -> crate::UrmResult<serde_json::Value> {
+                use crate::testing::eval;
+
+                mock::eval_binary::<<L::Ty as Type>::Unit>(
+                    eval(&self.0, scope)?,
+                    eval(&self.1, scope)?,
+                    $int_op,
+                    $float_op,
+                )
+            }
+        }
+    };
+}
+
+binary_operator!(
+    /// The sum of two numbers, also written `left + right`.
+    Add,
+    "+",
+    i64::checked_add,
+    |left, right| Some(left + right)
+);
+
+binary_operator!(
+    /// The difference of two numbers, also written `left - right`.
+    Sub,
+    "-",
+    i64::checked_sub,
+    |left, right| Some(left - right)
+);
+
+binary_operator!(
+    /// The product of two numbers, also written `left * right`.
+    Mul,
+    "*",
+    i64::checked_mul,
+    |left, right| Some(left * right)
+);
+
+binary_operator!(
+    /// The quotient of two numbers, also written `left / right`.
+    /// Integer division truncates towards zero.
+    Div,
+    "/",
+    i64::checked_div,
+    |left: f64, right: f64| match right == 0.0 {
+        true => None,
+        false => Some(left / right),
+    }
+);
+
+/// The SQL spelling of an operator on the numbers `N`, where integer division may differ.
+fn operator<DB: Database, N: Numeric>(sql: &'static str) -> &'static str {
+    match (sql, N::INTEGER) {
+        ("/", true) => DB::INTEGER_DIVISION,
+        _ => sql,
+    }
+}
+
+/// The negation of a number, also written `-expr`.
+#[derive(Clone)]
+pub struct Neg<E>(pub E);
+
+impl<DB, E> Typed<DB> for Neg<E>
+where
+    DB: Database,
+    E: Lower<DB> + Build<DB>,
+    E::Ty: ScalarType,
+    <E::Ty as Type>::Unit: Numeric,
+{
+    type Ty = E::Ty;
+}
+
+impl<DB, E> Lower<DB> for Neg<E>
+where
+    DB: Database,
+    E: Lower<DB> + Build<DB>,
+    E::Ty: ScalarType,
+    <E::Ty as Type>::Unit: Numeric,
+{
+    fn lower(self) -> Option<Lowered<DB>> {
+        Some(Lowered::Expr(Box::new(self)))
+    }
+}
+
+impl<DB, E> Build<DB> for Neg<E>
+where
+    DB: Database,
+    E: Lower<DB> + Build<DB>,
+    E::Ty: ScalarType,
+    <E::Ty as Type>::Unit: Numeric,
+{
+    /// The space keeps a negative operand from turning `-` into a `--` comment.
+    fn build(&self, builder: &mut QueryBuilder<DB>) {
+        builder.push("(- ");
+        self.0.build(builder);
+        builder.push(")");
+    }
+
+    #[cfg(feature = "testing")]
+    fn as_eval(&self) -> Option<&dyn crate::testing::Eval<DB>> {
+        Some(self)
+    }
+}
+
+#[cfg(feature = "testing")]
+mod mock {
+    use super::*;
+    use crate::testing::{eval, Eval, Scope};
+    use crate::{UrmError, UrmResult};
+    use serde_json::Value;
+
+    impl<DB, E> Eval<DB> for Neg<E>
+    where
+        DB: Database,
+        E: Typed<DB> + Build<DB>,
+        <E::Ty as Type>::Unit: Numeric,
+    {
+        fn eval(&self, scope: &Scope) -> UrmResult<Value> {
+            eval_binary::<<E::Ty as Type>::Unit>(
+                Value::from(0),
+                eval(&self.0, scope)?,
+                i64::checked_sub,
+                |zero, value| Some(zero - value),
+            )
+        }
+    }
+
+    /// Evaluate a binary operator in memory, on integers when `N` is an integer type.
+    pub(super) fn eval_binary<N: Numeric>(
+        left: Value,
+        right: Value,
+        int_op: impl Fn(i64, i64) -> Option<i64>,
+        float_op: impl Fn(f64, f64) -> Option<f64>,
+    ) -> UrmResult<Value> {
+        if left.is_null() || right.is_null() {
+            return Ok(Value::Null);
+        }
+
+        let result = match N::INTEGER {
+            true => left
+                .as_i64()
+                .zip(right.as_i64())
+                .map(|(left, right)| int_op(left, right).map(Value::from)),
+            false => left
+                .as_f64()
+                .zip(right.as_f64())
+                .map(|(left, right)| float_op(left, right).map(Value::from)),
+        };
+
+        match result {
+            Some(Some(value)) => Ok(value),
+            Some(None) => Err(UrmError::Eval(format!(
+                "arithmetic error on {} and {}",
+                left, right
+            ))),
+            None => Err(UrmError::Eval(format!(
+                "expected numbers, found {} and {}",
+                left, right
+            ))),
+        }
+    }
+}
+
+/// Implement the `std::ops` arithmetic operators for an expression type,
+/// building the corresponding `function` expressions.
+macro_rules! std_ops {
+    (impl<$($param:ident),*> for $ty:ty) => {
+        impl<$($param,)* Rhs> std::ops::Add<Rhs> for $ty {
+            type Output = Add<Self, Rhs>;
+
+            fn add(self, rhs: Rhs) -> Self::Output {
+                Add(self, rhs)
+            }
+        }
+
+        impl<$($param,)* Rhs> std::ops::Sub<Rhs> for $ty {
+            type Output = Sub<Self, Rhs>;
+
+            fn sub(self, rhs: Rhs) -> Self::Output {
+                Sub(self, rhs)
+            }
+        }
+
+        impl<$($param,)* Rhs> std::ops::Mul<Rhs> for $ty {
+            type Output = Mul<Self, Rhs>;
+
+            fn mul(self, rhs: Rhs) -> Self::Output {
+                Mul(self, rhs)
+            }
+        }
+
+        impl<$($param,)* Rhs> std::ops::Div<Rhs> for $ty {
+            type Output = Div<Self, Rhs>;
+
+            fn div(self, rhs: Rhs) -> Self::Output {
+                Div(self, rhs)
+            }
+        }
+
+        impl<$($param),*> std::ops::Neg for $ty {
+            type Output = Neg<Self>;
+
+            fn neg(self) -> Self::Output {
+                Neg(self)
+            }
+        }
+    };
+}
+
+std_ops!(impl<T, Ty> for Column<T, Ty>);
+std_ops!(impl<T, E> for Computed<T, E>);
+std_ops!(impl<T> for Scalar<T>);
+std_ops!(impl<L, R> for Add<L, R>);
+std_ops!(impl<L, R> for Sub<L, R>);
+std_ops!(impl<L, R> for Mul<L, R>);
+std_ops!(impl<L, R> for Div<L, R>);
+std_ops!(impl<E> for Neg<E>);
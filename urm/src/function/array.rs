@@ -0,0 +1,270 @@
+use crate::builder::Build;
+use crate::database::Database;
+use crate::lower::{Lower, Lowered};
+use crate::ty::{Nullable, Type, Typed, Unit, VectorTyped};
+
+/// Binary function that tests whether the left vector contains every item of the right vector,
+/// i.e. `@>` of Postgres arrays.
+#[derive(Clone)]
+pub struct ArrayContains<L, R>(pub L, pub R);
+
+/// Binary function that tests whether two vectors have any item in common,
+/// i.e. `&&` of Postgres arrays.
+#[derive(Clone)]
+pub struct Overlaps<L, R>(pub L, pub R);
+
+/// The number of items in a vector, which is 0 for an empty vector.
+#[derive(Clone)]
+pub struct ArrayLength<E>(pub E);
+
+/// Define the type of a binary function of two vectors having the same unit, yielding a bool.
+macro_rules! vector_predicate {
+    ($ident:ident) => {
+        impl<DB, L, R> Typed<DB> for $ident<L, R>
+        where
+            DB: Database,
+            L: Lower<DB> + Build<DB> + VectorTyped<DB, <L::Ty as Type>::Unit>,
+            R: Lower<DB> + Build<DB> + VectorTyped<DB, <L::Ty as Type>::Unit>,
+        {
+            type Ty = Nullable<bool>;
+        }
+
+        impl<DB, L, R> Lower<DB> for $ident<L, R>
+        where
+            DB: Database,
+            L: Lower<DB> + Build<DB> + VectorTyped<DB, <L::Ty as Type>::Unit>,
+            R: Lower<DB> + Build<DB> + VectorTyped<DB, <L::Ty as Type>::Unit>,
+            Self: Build<DB>,
+        {
+            fn lower(self) -> Option<Lowered<DB>> {
+                Some(Lowered::Expr(Box::new(self)))
+            }
+        }
+    };
+}
+
+vector_predicate!(ArrayContains);
+vector_predicate!(Overlaps);
+
+impl<DB, E> Typed<DB> for ArrayLength<E>
+where
+    DB: Database,
+    E: Lower<DB> + Build<DB> + VectorTyped<DB, <E::Ty as Type>::Unit>,
+{
+    type Ty = Unit<i64>;
+}
+
+impl<DB, E> Lower<DB> for ArrayLength<E>
+where
+    DB: Database,
+    E: Lower<DB> + Build<DB> + VectorTyped<DB, <E::Ty as Type>::Unit>,
+    Self: Build<DB>,
+{
+    fn lower(self) -> Option<Lowered<DB>> {
+        Some(Lowered::Expr(Box::new(self)))
+    }
+}
+
+#[cfg(feature = "postgres")]
+mod postgres {
+    use super::*;
+    use crate::builder::QueryBuilder;
+    use crate::database::Postgres;
+
+    /// Build a binary operator of Postgres arrays.
+    fn build_operator<DB: Database, L: Build<DB>, R: Build<DB>>(
+        left: &L,
+        operator: &str,
+        right: &R,
+        builder: &mut QueryBuilder<DB>,
+    ) {
+        builder.push("(");
+        left.build(builder);
+        builder.push(operator);
+        right.build(builder);
+        builder.push(")");
+    }
+
+    /// `cardinality` counts all items of an array, while `array_length` of an empty array is null.
+    fn build_cardinality<DB: Database, E: Build<DB>>(expr: &E, builder: &mut QueryBuilder<DB>) {
+        builder.push("cardinality(");
+        expr.build(builder);
+        builder.push(")");
+    }
+
+    impl<L, R> Build<Postgres> for ArrayContains<L, R>
+    where
+        L: Lower<Postgres> + Build<Postgres> + VectorTyped<Postgres, <L::Ty as Type>::Unit>,
+        R: Lower<Postgres> + Build<Postgres> + VectorTyped<Postgres, <L::Ty as Type>::Unit>,
+    {
+        fn build(&self, builder: &mut QueryBuilder<Postgres>) {
+            build_operator(&self.0, " @> ", &self.1, builder);
+        }
+
+        #[cfg(feature = "testing")]
+        fn as_eval(&self) -> Option<&dyn crate::testing::Eval<Postgres>> {
+            Some(self)
+        }
+    }
+
+    impl<L, R> Build<Postgres> for Overlaps<L, R>
+    where
+        L: Lower<Postgres> + Build<Postgres> + VectorTyped<Postgres, <L::Ty as Type>::Unit>,
+        R: Lower<Postgres> + Build<Postgres> + VectorTyped<Postgres, <L::Ty as Type>::Unit>,
+    {
+        fn build(&self, builder: &mut QueryBuilder<Postgres>) {
+            build_operator(&self.0, " && ", &self.1, builder);
+        }
+
+        #[cfg(feature = "testing")]
+        fn as_eval(&self) -> Option<&dyn crate::testing::Eval<Postgres>> {
+            Some(self)
+        }
+    }
+
+    impl<E> Build<Postgres> for ArrayLength<E>
+    where
+        E: Lower<Postgres> + Build<Postgres> + VectorTyped<Postgres, <E::Ty as Type>::Unit>,
+    {
+        fn build(&self, builder: &mut QueryBuilder<Postgres>) {
+            build_cardinality(&self.0, builder);
+        }
+
+        #[cfg(feature = "testing")]
+        fn as_eval(&self) -> Option<&dyn crate::testing::Eval<Postgres>> {
+            Some(self)
+        }
+    }
+}
+
+/// Vectors are JSON arrays in SQLite, compared as sets of the values of `json_each`.
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use super::*;
+    use crate::builder::QueryBuilder;
+    use crate::database::Sqlite;
+
+    impl<L, R> Build<Sqlite> for ArrayContains<L, R>
+    where
+        L: Lower<Sqlite> + Build<Sqlite> + VectorTyped<Sqlite, <L::Ty as Type>::Unit>,
+        R: Lower<Sqlite> + Build<Sqlite> + VectorTyped<Sqlite, <L::Ty as Type>::Unit>,
+    {
+        fn build(&self, builder: &mut QueryBuilder<Sqlite>) {
+            builder.push("NOT EXISTS (SELECT value FROM json_each(");
+            self.1.build(builder);
+            builder.push(") EXCEPT SELECT value FROM json_each(");
+            self.0.build(builder);
+            builder.push("))");
+        }
+
+        #[cfg(feature = "testing")]
+        fn as_eval(&self) -> Option<&dyn crate::testing::Eval<Sqlite>> {
+            Some(self)
+        }
+    }
+
+    impl<L, R> Build<Sqlite> for Overlaps<L, R>
+    where
+        L: Lower<Sqlite> + Build<Sqlite> + VectorTyped<Sqlite, <L::Ty as Type>::Unit>,
+        R: Lower<Sqlite> + Build<Sqlite> + VectorTyped<Sqlite, <L::Ty as Type>::Unit>,
+    {
+        fn build(&self, builder: &mut QueryBuilder<Sqlite>) {
+            builder.push("EXISTS (SELECT value FROM json_each(");
+            self.0.build(builder);
+            builder.push(") INTERSECT SELECT value FROM json_each(");
+            self.1.build(builder);
+            builder.push("))");
+        }
+
+        #[cfg(feature = "testing")]
+        fn as_eval(&self) -> Option<&dyn crate::testing::Eval<Sqlite>> {
+            Some(self)
+        }
+    }
+
+    impl<E> Build<Sqlite> for ArrayLength<E>
+    where
+        E: Lower<Sqlite> + Build<Sqlite> + VectorTyped<Sqlite, <E::Ty as Type>::Unit>,
+    {
+        fn build(&self, builder: &mut QueryBuilder<Sqlite>) {
+            builder.push("json_array_length(");
+            self.0.build(builder);
+            builder.push(")");
+        }
+
+        #[cfg(feature = "testing")]
+        fn as_eval(&self) -> Option<&dyn crate::testing::Eval<Sqlite>> {
+            Some(self)
+        }
+    }
+}
+
+#[cfg(feature = "testing")]
+mod mock {
+    use super::*;
+    use crate::testing::{eval, Eval, Scope};
+    use crate::{UrmError, UrmResult};
+
+    impl<DB, L, R> Eval<DB> for ArrayContains<L, R>
+    where
+        DB: Database,
+        L: Build<DB>,
+        R: Build<DB>,
+    {
+        fn eval(&self, scope: &Scope) -> UrmResult<serde_json::Value> {
+            eval_arrays(
+                eval(&self.0, scope)?,
+                eval(&self.1, scope)?,
+                |left, right| right.iter().all(|item| left.contains(item)),
+            )
+        }
+    }
+
+    impl<DB, L, R> Eval<DB> for Overlaps<L, R>
+    where
+        DB: Database,
+        L: Build<DB>,
+        R: Build<DB>,
+    {
+        fn eval(&self, scope: &Scope) -> UrmResult<serde_json::Value> {
+            eval_arrays(
+                eval(&self.0, scope)?,
+                eval(&self.1, scope)?,
+                |left, right| right.iter().any(|item| left.contains(item)),
+            )
+        }
+    }
+
+    impl<DB, E> Eval<DB> for ArrayLength<E>
+    where
+        DB: Database,
+        E: Build<DB>,
+    {
+        fn eval(&self, scope: &Scope) -> UrmResult<serde_json::Value> {
+            match eval(&self.0, scope)? {
+                serde_json::Value::Null => Ok(serde_json::Value::Null),
+                serde_json::Value::Array(items) => Ok(items.len().into()),
+                value => Err(UrmError::Eval(format!("expected array, found {}", value))),
+            }
+        }
+    }
+    /// Evaluate a predicate of two arrays in memory, where a null array yields null.
+    fn eval_arrays(
+        left: serde_json::Value,
+        right: serde_json::Value,
+        func: impl Fn(&[serde_json::Value], &[serde_json::Value]) -> bool,
+    ) -> UrmResult<serde_json::Value> {
+        match (left, right) {
+            (serde_json::Value::Null, _) | (_, serde_json::Value::Null) => {
+                Ok(serde_json::Value::Null)
+            }
+            (serde_json::Value::Array(left), serde_json::Value::Array(right)) => {
+                Ok(serde_json::Value::Bool(func(&left, &right)))
+            }
+            (left, right) => Err(UrmError::Eval(format!(
+                "expected arrays, found {} and {}",
+                left, right
+            ))),
+        }
+    }
+}
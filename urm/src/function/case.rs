@@ -0,0 +1,269 @@
+use crate::builder::{Build, QueryBuilder};
+use crate::database::Database;
+use crate::lower::{Lower, Lowered};
+use crate::ty::{JoinNullable, Nullable, ScalarType, ScalarTyped, Type, Typed};
+
+#[cfg(feature = "testing")]
+use crate::testing::{eval, Eval, Scope};
+#[cfg(feature = "testing")]
+use crate::{UrmError, UrmResult};
+
+///
+/// A conditional expression, built like
+///
+/// ```ignore
+/// Case::when(pred, value).when(pred, value).otherwise(value)
+/// ```
+///
+/// Every value has the unit of the first one. The result is `Nullable`
+/// when there is no `otherwise`, or when any of the values is nullable.
+///
+pub struct Case;
+
+impl Case {
+    /// The first branch, yielding `value` when `pred` is true.
+    pub fn when<P, V>(pred: P, value: V) -> CaseWhen<When<P, V>, ()> {
+        CaseWhen {
+            branches: When { pred, value },
+            otherwise: (),
+        }
+    }
+}
+
+/// A `Case` expression with the branches `B`, and the value `O` when no branch applies.
+#[derive(Clone)]
+pub struct CaseWhen<B, O> {
+    branches: B,
+    otherwise: O,
+}
+
+impl<B> CaseWhen<B, ()> {
+    /// Another branch, tried when the previous ones don't apply.
+    pub fn when<P, V>(self, pred: P, value: V) -> CaseWhen<ElseWhen<B, P, V>, ()> {
+        CaseWhen {
+            branches: ElseWhen {
+                prev: self.branches,
+                pred,
+                value,
+            },
+            otherwise: (),
+        }
+    }
+
+    /// The value when no branch applies.
+    pub fn otherwise<V>(self, value: V) -> CaseWhen<B, Otherwise<V>> {
+        CaseWhen {
+            branches: self.branches,
+            otherwise: Otherwise(value),
+        }
+    }
+}
+
+/// The first `WHEN pred THEN value` branch.
+#[derive(Clone)]
+pub struct When<P, V> {
+    pred: P,
+    value: V,
+}
+
+/// A `WHEN pred THEN value` branch, following the branches `Prev`.
+#[derive(Clone)]
+pub struct ElseWhen<Prev, P, V> {
+    prev: Prev,
+    pred: P,
+    value: V,
+}
+
+/// The `ELSE value` of a `Case`.
+#[derive(Clone)]
+pub struct Otherwise<V>(V);
+
+///
+/// The branches of a `Case`, having the type `Ty` with nullability joined from all values.
+///
+pub trait Branches<DB: Database>: Send + Sync + 'static {
+    type Ty: ScalarType;
+
+    fn build_branches(&self, builder: &mut QueryBuilder<DB>);
+
+    /// The value of the first applying branch, if any.
+    #[cfg(feature = "testing")]
+    fn eval_branches(&self, scope: &Scope) -> UrmResult<Option<serde_json::Value>>;
+}
+
+impl<DB, P, V> Branches<DB> for When<P, V>
+where
+    DB: Database,
+    P: Lower<DB> + Build<DB> + ScalarTyped<DB, bool>,
+    V: Lower<DB> + Build<DB>,
+    V::Ty: ScalarType,
+{
+    type Ty = V::Ty;
+
+    fn build_branches(&self, builder: &mut QueryBuilder<DB>) {
+        build_branch(&self.pred, &self.value, builder);
+    }
+
+    #[cfg(feature = "testing")]
+    fn eval_branches(&self, scope: &Scope) -> UrmResult<Option<serde_json::Value>> {
+        eval_branch(&self.pred, &self.value, scope)
+    }
+}
+
+impl<DB, Prev, P, V> Branches<DB> for ElseWhen<Prev, P, V>
+where
+    DB: Database,
+    Prev: Branches<DB>,
+    P: Lower<DB> + Build<DB> + ScalarTyped<DB, bool>,
+    V: Lower<DB> + Build<DB> + ScalarTyped<DB, <Prev::Ty as Type>::Unit>,
+    Prev::Ty: JoinNullable<V::Ty, <Prev::Ty as Type>::Unit>,
+{
+    type Ty = <Prev::Ty as JoinNullable<V::Ty, <Prev::Ty as Type>::Unit>>::Ty;
+
+    fn build_branches(&self, builder: &mut QueryBuilder<DB>) {
+        self.prev.build_branches(builder);
+        builder.newline();
+        build_branch(&self.pred, &self.value, builder);
+    }
+
+    #[cfg(feature = "testing")]
+    fn eval_branches(&self, scope: &Scope) -> UrmResult<Option<serde_json::Value>> {
+        match self.prev.eval_branches(scope)? {
+            Some(value) => Ok(Some(value)),
+            None => eval_branch(&self.pred, &self.value, scope),
+        }
+    }
+}
+
+///
+/// What a `Case` yields when no branch applies, deciding the type of the `Case`
+/// given the type `B` of its branches.
+///
+pub trait CaseOtherwise<DB: Database, B: ScalarType>: Send + Sync + 'static {
+    type Ty: ScalarType<Unit = B::Unit>;
+
+    fn build_otherwise(&self, builder: &mut QueryBuilder<DB>);
+
+    #[cfg(feature = "testing")]
+    fn eval_otherwise(&self, scope: &Scope) -> UrmResult<serde_json::Value>;
+}
+
+/// Without `otherwise`, a `Case` where no branch applies is null.
+impl<DB, B> CaseOtherwise<DB, B> for ()
+where
+    DB: Database,
+    B: ScalarType,
+{
+    type Ty = Nullable<B::Unit>;
+
+    fn build_otherwise(&self, _builder: &mut QueryBuilder<DB>) {}
+
+    #[cfg(feature = "testing")]
+    fn eval_otherwise(&self, _scope: &Scope) -> UrmResult<serde_json::Value> {
+        Ok(serde_json::Value::Null)
+    }
+}
+
+impl<DB, B, V> CaseOtherwise<DB, B> for Otherwise<V>
+where
+    DB: Database,
+    B: ScalarType + JoinNullable<V::Ty, <B as Type>::Unit>,
+    V: Lower<DB> + Build<DB> + ScalarTyped<DB, <B as Type>::Unit>,
+{
+    type Ty = <B as JoinNullable<V::Ty, <B as Type>::Unit>>::Ty;
+
+    fn build_otherwise(&self, builder: &mut QueryBuilder<DB>) {
+        builder.newline();
+        builder.push("ELSE ");
+        self.0.build(builder);
+    }
+
+    #[cfg(feature = "testing")]
+    fn eval_otherwise(&self, scope: &Scope) -> UrmResult<serde_json::Value> {
+        eval(&self.0, scope)
+    }
+}
+
+impl<DB, B, O> Typed<DB> for CaseWhen<B, O>
+where
+    DB: Database,
+    B: Branches<DB>,
+    O: CaseOtherwise<DB, B::Ty>,
+{
+    type Ty = O::Ty;
+}
+
+impl<DB, B, O> Lower<DB> for CaseWhen<B, O>
+where
+    DB: Database,
+    B: Branches<DB>,
+    O: CaseOtherwise<DB, B::Ty>,
+{
+    fn lower(self) -> Option<Lowered<DB>> {
+        Some(Lowered::Expr(Box::new(self)))
+    }
+}
+
+impl<DB, B, O> Build<DB> for CaseWhen<B, O>
+where
+    DB: Database,
+    B: Branches<DB>,
+    O: CaseOtherwise<DB, B::Ty>,
+{
+    fn build(&self, builder: &mut QueryBuilder<DB>) {
+        builder.push("CASE");
+        builder.newline_indent();
+        self.branches.build_branches(builder);
+        self.otherwise.build_otherwise(builder);
+        builder.newline_outdent();
+        builder.push("END");
+    }
+
+    #[cfg(feature = "testing")]
+    fn as_eval(&self) -> Option<&dyn Eval<DB>> {
+        Some(self)
+    }
+}
+
+#[cfg(feature = "testing")]
+impl<DB, B, O> Eval<DB> for CaseWhen<B, O>
+where
+    DB: Database,
+    B: Branches<DB>,
+    O: CaseOtherwise<DB, B::Ty>,
+{
+    fn eval(&self, scope: &Scope) -> UrmResult<serde_json::Value> {
+        match self.branches.eval_branches(scope)? {
+            Some(value) => Ok(value),
+            None => self.otherwise.eval_otherwise(scope),
+        }
+    }
+}
+
+fn build_branch<DB: Database>(
+    pred: &dyn Build<DB>,
+    value: &dyn Build<DB>,
+    builder: &mut QueryBuilder<DB>,
+) {
+    builder.push("WHEN ");
+    pred.build(builder);
+    builder.push(" THEN ");
+    value.build(builder);
+}
+
+/// A branch applies when its predicate is true, not when it is false or null.
+#[cfg(feature = "testing")]
+fn eval_branch<DB: Database>(
+    pred: &dyn Build<DB>,
+    value: &dyn Build<DB>,
+    scope: &Scope,
+) -> UrmResult<Option<serde_json::Value>> {
+    match eval(pred, scope)? {
+        serde_json::Value::Bool(true) => eval(value, scope).map(Some),
+        serde_json::Value::Bool(false) | serde_json::Value::Null => Ok(None),
+        other => Err(UrmError::Eval(format!(
+            "expected a boolean predicate, found {}",
+            other
+        ))),
+    }
+}
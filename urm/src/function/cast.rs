@@ -0,0 +1,218 @@
+use crate::builder::{Build, QueryBuilder};
+use crate::database::Database;
+use crate::lower::{Lower, Lowered};
+use crate::ty::{MapUnit, ScalarType, Typed};
+
+#[cfg(feature = "testing")]
+use crate::{UrmError, UrmResult};
+
+///
+/// A Rust type that expressions may be cast to, naming its SQL type in the database `DB`.
+///
+pub trait SqlType<DB: Database>: Send + Sync + 'static {
+    /// The type as written in `CAST(expr AS type)`.
+    const NAME: &'static str;
+
+    /// Cast a value in memory, as done by the `testing::MockDatabase`.
+    #[cfg(feature = "testing")]
+    fn eval_cast(_value: serde_json::Value) -> UrmResult<serde_json::Value> {
+        Err(UrmError::Eval(format!(
+            "can't cast to {} in memory",
+            Self::NAME
+        )))
+    }
+}
+
+/// The operand converted to the type `To`, keeping its nullability.
+///
+/// `Cast::<String, _>::new(db::Edition.year())`
+pub struct Cast<To, E> {
+    expr: E,
+    to: std::marker::PhantomData<To>,
+}
+
+impl<To, E> Cast<To, E> {
+    pub fn new(expr: E) -> Self {
+        Self {
+            expr,
+            to: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<To, E: Clone> Clone for Cast<To, E> {
+    fn clone(&self) -> Self {
+        Self::new(self.expr.clone())
+    }
+}
+
+impl<DB, To, E> Typed<DB> for Cast<To, E>
+where
+    DB: Database,
+    To: SqlType<DB>,
+    E: Lower<DB> + Build<DB>,
+    E::Ty: ScalarType + MapUnit<To>,
+{
+    type Ty = <E::Ty as MapUnit<To>>::Ty;
+}
+
+impl<DB, To, E> Lower<DB> for Cast<To, E>
+where
+    DB: Database,
+    To: SqlType<DB>,
+    E: Lower<DB> + Build<DB>,
+    E::Ty: ScalarType + MapUnit<To>,
+{
+    fn lower(self) -> Option<Lowered<DB>> {
+        Some(Lowered::Expr(Box::new(self)))
+    }
+}
+
+impl<DB, To, E> Build<DB> for Cast<To, E>
+where
+    DB: Database,
+    To: SqlType<DB>,
+    E: Lower<DB> + Build<DB>,
+    E::Ty: ScalarType + MapUnit<To>,
+{
+    fn build(&self, builder: &mut QueryBuilder<DB>) {
+        builder.push("CAST(");
+        self.expr.build(builder);
+        builder.push(" AS ");
+        builder.push(To::NAME);
+        builder.push(")");
+    }
+
+    #[cfg(feature = "testing")]
+    fn as_eval(&self) -> Option<&dyn crate::testing::Eval<DB>> {
+        Some(self)
+    }
+}
+
+#[cfg(feature = "testing")]
+impl<DB, To, E> crate::testing::Eval<DB> for Cast<To, E>
+where
+    DB: Database,
+    To: SqlType<DB>,
+    E: Build<DB>,
+{
+    fn eval(&self, scope: &crate::testing::Scope) -> UrmResult<serde_json::Value> {
+        match crate::testing::eval(&self.expr, scope)? {
+            serde_json::Value::Null => Ok(serde_json::Value::Null),
+            value => To::eval_cast(value),
+        }
+    }
+}
+
+/// Implement `SqlType` for Rust types in one database.
+/// Casts evaluate in memory the same way in every database, see `mock::EvalCast`.
+#[allow(unused_macros)]
+macro_rules! sql_types {
+    ($db:ty: $($ty:ty => $name:literal),+) => {
+        $(
+            impl SqlType<$db> for $ty {
+                const NAME: &'static str = $name;
+
+                #[cfg(feature = "testing")]
+                fn eval_cast(value: serde_json::Value) -> UrmResult<serde_json::Value> {
+                    <$ty as mock::EvalCast>::eval_cast(value)
+                }
+            }
+        )+
+    };
+}
+
+#[cfg(feature = "postgres")]
+sql_types!(crate::database::Postgres:
+    String => "TEXT",
+    i32 => "INTEGER",
+    i64 => "BIGINT",
+    f64 => "DOUBLE PRECISION",
+    bool => "BOOLEAN"
+);
+
+// SQLite has no boolean type, booleans are the integers `1` and `0`.
+#[cfg(feature = "sqlite")]
+sql_types!(crate::database::Sqlite:
+    String => "TEXT",
+    i32 => "INTEGER",
+    i64 => "INTEGER",
+    f64 => "REAL",
+    bool => "INTEGER"
+);
+
+// MySQL casts to a restricted set of types, where integers are `SIGNED`.
+#[cfg(feature = "mysql")]
+sql_types!(crate::database::MySql:
+    String => "CHAR",
+    i32 => "SIGNED",
+    i64 => "SIGNED",
+    f64 => "DOUBLE",
+    bool => "SIGNED"
+);
+
+#[cfg(feature = "testing")]
+mod mock {
+    use super::*;
+    use serde_json::Value;
+
+    /// Cast a value to this type in memory.
+    pub(super) trait EvalCast {
+        fn eval_cast(value: Value) -> UrmResult<Value>;
+    }
+
+    impl EvalCast for String {
+        fn eval_cast(value: Value) -> UrmResult<Value> {
+            Ok(match value {
+                Value::String(string) => Value::String(string),
+                value => Value::String(value.to_string()),
+            })
+        }
+    }
+
+    impl EvalCast for i32 {
+        fn eval_cast(value: Value) -> UrmResult<Value> {
+            eval_cast_number::<i32>(value)
+        }
+    }
+
+    impl EvalCast for i64 {
+        fn eval_cast(value: Value) -> UrmResult<Value> {
+            eval_cast_number::<i64>(value)
+        }
+    }
+
+    impl EvalCast for f64 {
+        fn eval_cast(value: Value) -> UrmResult<Value> {
+            eval_cast_number::<f64>(value)
+        }
+    }
+
+    impl EvalCast for bool {
+        fn eval_cast(value: Value) -> UrmResult<Value> {
+            match value {
+                Value::Bool(value) => Ok(Value::Bool(value)),
+                Value::String(string) if string == "true" => Ok(Value::Bool(true)),
+                Value::String(string) if string == "false" => Ok(Value::Bool(false)),
+                value => Err(UrmError::Eval(format!("can't cast {} to BOOLEAN", value))),
+            }
+        }
+    }
+
+    /// Cast a number or a text to the number type `N`.
+    fn eval_cast_number<N>(value: Value) -> UrmResult<Value>
+    where
+        N: std::str::FromStr + serde::Serialize,
+    {
+        let text = match &value {
+            Value::String(string) => string.trim().to_string(),
+            Value::Number(number) => number.to_string(),
+            _ => return Err(UrmError::Eval(format!("can't cast {} to a number", value))),
+        };
+
+        text.parse::<N>()
+            .ok()
+            .and_then(|number| serde_json::to_value(number).ok())
+            .ok_or_else(|| UrmError::Eval(format!("can't cast {} to a number", value)))
+    }
+}
@@ -0,0 +1,72 @@
+use crate::builder::{Build, QueryBuilder};
+use crate::database::Database;
+use crate::lower::{Lower, Lowered};
+use crate::ty::{ScalarType, ScalarTyped, Type, Typed};
+
+/// The first operand if not null, otherwise the second,
+/// e.g. `Coalesce(Nullable<T>, Unit<T>)` is a `Unit<T>`.
+#[derive(Clone)]
+pub struct Coalesce<A, B>(pub A, pub B);
+
+impl<DB, A, B> Typed<DB> for Coalesce<A, B>
+where
+    DB: Database,
+    A: Lower<DB> + Build<DB>,
+    B: Lower<DB> + Build<DB> + ScalarTyped<DB, <A::Ty as Type>::Unit>,
+    A::Ty: ScalarType,
+    B::Ty: ScalarType,
+{
+    type Ty = B::Ty;
+}
+
+impl<DB, A, B> Lower<DB> for Coalesce<A, B>
+where
+    DB: Database,
+    A: Lower<DB> + Build<DB>,
+    B: Lower<DB> + Build<DB> + ScalarTyped<DB, <A::Ty as Type>::Unit>,
+    A::Ty: ScalarType,
+    B::Ty: ScalarType,
+{
+    fn lower(self) -> Option<Lowered<DB>> {
+        Some(Lowered::Expr(Box::new(self)))
+    }
+}
+
+impl<DB, A, B> Build<DB> for Coalesce<A, B>
+where
+    DB: Database,
+    A: Lower<DB> + Build<DB>,
+    B: Lower<DB> + Build<DB> + ScalarTyped<DB, <A::Ty as Type>::Unit>,
+    A::Ty: ScalarType,
+    B::Ty: ScalarType,
+{
+    fn build(&self, builder: &mut QueryBuilder<DB>) {
+        builder.push("COALESCE(");
+        self.0.build(builder);
+        builder.push(", ");
+        self.1.build(builder);
+        builder.push(")");
+    }
+
+    #[cfg(feature = "testing")]
+    fn as_eval(&self) -> Option<&dyn crate::testing::Eval<DB>> {
+        Some(self)
+    }
+}
+
+#[cfg(feature = "testing")]
+impl<DB, A, B> crate::testing::Eval<DB> for Coalesce<A, B>
+where
+    DB: Database,
+    A: Build<DB>,
+    B: Build<DB>,
+{
+    fn eval(&self, scope: &crate::testing::Scope) -> crate::UrmResult<serde_json::Value> {
+        use crate::testing::eval;
+
+        match eval(&self.0, scope)? {
+            serde_json::Value::Null => eval(&self.1, scope),
+            value => Ok(value),
+        }
+    }
+}
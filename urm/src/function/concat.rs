@@ -0,0 +1,145 @@
+use crate::builder::Build;
+use crate::database::Database;
+use crate::lower::{Lower, Lowered};
+use crate::ty::{JoinNullable, ScalarTyped, Typed};
+
+/// The concatenation of two text operands, which is null if either operand is.
+#[derive(Clone)]
+pub struct Concat<L, R>(pub L, pub R);
+
+impl<DB, L, R> Typed<DB> for Concat<L, R>
+where
+    DB: Database,
+    L: Lower<DB> + Build<DB> + ScalarTyped<DB, String>,
+    R: Lower<DB> + Build<DB> + ScalarTyped<DB, String>,
+    L::Ty: JoinNullable<R::Ty, String>,
+{
+    type Ty = <L::Ty as JoinNullable<R::Ty, String>>::Ty;
+}
+
+impl<DB, L, R> Lower<DB> for Concat<L, R>
+where
+    DB: Database,
+    L: Lower<DB> + Build<DB> + ScalarTyped<DB, String>,
+    R: Lower<DB> + Build<DB> + ScalarTyped<DB, String>,
+    L::Ty: JoinNullable<R::Ty, String>,
+    Self: Build<DB>,
+{
+    fn lower(self) -> Option<Lowered<DB>> {
+        Some(Lowered::Expr(Box::new(self)))
+    }
+}
+
+/// Build `Concat` using the standard `||` operator.
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+fn build_operator<DB: Database, L: Build<DB>, R: Build<DB>>(
+    concat: &Concat<L, R>,
+    builder: &mut crate::builder::QueryBuilder<DB>,
+) {
+    builder.push("(");
+    concat.0.build(builder);
+    builder.push(" || ");
+    concat.1.build(builder);
+    builder.push(")");
+}
+
+#[cfg(feature = "postgres")]
+mod postgres {
+    use super::*;
+    use crate::builder::QueryBuilder;
+    use crate::database::Postgres;
+
+    impl<L, R> Build<Postgres> for Concat<L, R>
+    where
+        L: Lower<Postgres> + Build<Postgres> + ScalarTyped<Postgres, String>,
+        R: Lower<Postgres> + Build<Postgres> + ScalarTyped<Postgres, String>,
+        L::Ty: JoinNullable<R::Ty, String>,
+    {
+        fn build(&self, builder: &mut QueryBuilder<Postgres>) {
+            build_operator(self, builder);
+        }
+
+        #[cfg(feature = "testing")]
+        fn as_eval(&self) -> Option<&dyn crate::testing::Eval<Postgres>> {
+            Some(self)
+        }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use super::*;
+    use crate::builder::QueryBuilder;
+    use crate::database::Sqlite;
+
+    impl<L, R> Build<Sqlite> for Concat<L, R>
+    where
+        L: Lower<Sqlite> + Build<Sqlite> + ScalarTyped<Sqlite, String>,
+        R: Lower<Sqlite> + Build<Sqlite> + ScalarTyped<Sqlite, String>,
+        L::Ty: JoinNullable<R::Ty, String>,
+    {
+        fn build(&self, builder: &mut QueryBuilder<Sqlite>) {
+            build_operator(self, builder);
+        }
+
+        #[cfg(feature = "testing")]
+        fn as_eval(&self) -> Option<&dyn crate::testing::Eval<Sqlite>> {
+            Some(self)
+        }
+    }
+}
+
+#[cfg(feature = "mysql")]
+mod mysql {
+    use super::*;
+    use crate::builder::QueryBuilder;
+    use crate::database::MySql;
+
+    /// In MySQL `||` is a logical or, so the `CONCAT` function is used.
+    impl<L, R> Build<MySql> for Concat<L, R>
+    where
+        L: Lower<MySql> + Build<MySql> + ScalarTyped<MySql, String>,
+        R: Lower<MySql> + Build<MySql> + ScalarTyped<MySql, String>,
+        L::Ty: JoinNullable<R::Ty, String>,
+    {
+        fn build(&self, builder: &mut QueryBuilder<MySql>) {
+            builder.push("CONCAT(");
+            self.0.build(builder);
+            builder.push(", ");
+            self.1.build(builder);
+            builder.push(")");
+        }
+
+        #[cfg(feature = "testing")]
+        fn as_eval(&self) -> Option<&dyn crate::testing::Eval<MySql>> {
+            Some(self)
+        }
+    }
+}
+
+#[cfg(feature = "testing")]
+mod mock {
+    use super::*;
+    use crate::testing::{eval, Eval, Scope};
+    use crate::{UrmError, UrmResult};
+
+    impl<DB, L, R> Eval<DB> for Concat<L, R>
+    where
+        DB: Database,
+        L: Build<DB>,
+        R: Build<DB>,
+    {
+        fn eval(&self, scope: &Scope) -> UrmResult<serde_json::Value> {
+            use serde_json::Value;
+
+            match (eval(&self.0, scope)?, eval(&self.1, scope)?) {
+                (Value::Null, _) | (_, Value::Null) => Ok(Value::Null),
+                (Value::String(left), Value::String(right)) => Ok(Value::String(left + &right)),
+                (left, right) => Err(UrmError::Eval(format!(
+                    "expected text, found {} and {}",
+                    left, right
+                ))),
+            }
+        }
+    }
+}
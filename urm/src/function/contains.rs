@@ -1,9 +1,10 @@
-use crate::builder::{Build, QueryBuilder};
+use crate::builder::Build;
 use crate::database::Database;
-use crate::lower::{Lower, Lowered};
+use crate::lower::Lower;
 use crate::ty::{Nullable, ScalarTyped, Type, Typed, VectorTyped};
 
 /// Binary function that tests equality between two given operands
+#[derive(Clone)]
 pub struct Contains<V, I>(pub V, pub I);
 
 impl<DB, V, I> Typed<DB> for Contains<V, I>
@@ -18,7 +19,9 @@ where
 #[cfg(feature = "postgres")]
 mod postgres {
     use super::*;
+    use crate::builder::QueryBuilder;
     use crate::database::Postgres;
+    use crate::lower::Lowered;
 
     impl<V, I> Lower<Postgres> for Contains<V, I>
     where
@@ -42,5 +45,113 @@ mod postgres {
             self.0.build(builder);
             builder.push(")");
         }
+
+        #[cfg(feature = "testing")]
+        fn as_eval(&self) -> Option<&dyn crate::testing::Eval<Postgres>> {
+            Some(self)
+        }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use super::*;
+    use crate::builder::QueryBuilder;
+    use crate::database::Sqlite;
+    use crate::lower::Lowered;
+
+    impl<V, I> Lower<Sqlite> for Contains<V, I>
+    where
+        V: Lower<Sqlite> + Build<Sqlite> + VectorTyped<Sqlite, <V::Ty as Type>::Unit>,
+        I: Lower<Sqlite> + Build<Sqlite> + ScalarTyped<Sqlite, <V::Ty as Type>::Unit>,
+    {
+        fn lower(self) -> Option<Lowered<Sqlite>> {
+            Some(Lowered::Expr(Box::new(self)))
+        }
+    }
+
+    /// The vector is a JSON array, unpacked into a list of values by `json_each`.
+    impl<V, I> Build<Sqlite> for Contains<V, I>
+    where
+        V: Lower<Sqlite> + Build<Sqlite> + VectorTyped<Sqlite, <V::Ty as Type>::Unit>,
+        I: Lower<Sqlite> + Build<Sqlite> + ScalarTyped<Sqlite, <V::Ty as Type>::Unit>,
+    {
+        fn build(&self, builder: &mut QueryBuilder<Sqlite>) {
+            self.1.build(builder);
+            builder.push(" IN (SELECT value FROM json_each(");
+            self.0.build(builder);
+            builder.push("))");
+        }
+
+        #[cfg(feature = "testing")]
+        fn as_eval(&self) -> Option<&dyn crate::testing::Eval<Sqlite>> {
+            Some(self)
+        }
+    }
+}
+
+#[cfg(feature = "mysql")]
+mod mysql {
+    use super::*;
+    use crate::builder::QueryBuilder;
+    use crate::database::MySql;
+    use crate::lower::Lowered;
+    use crate::value::Vector;
+
+    type VectorUnit<T> = <<Vector<T> as Typed<MySql>>::Ty as Type>::Unit;
+
+    impl<T, I> Lower<MySql> for Contains<Vector<T>, I>
+    where
+        Vector<T>: Lower<MySql> + Build<MySql> + VectorTyped<MySql, VectorUnit<T>>,
+        I: Lower<MySql> + Build<MySql> + ScalarTyped<MySql, VectorUnit<T>>,
+    {
+        fn lower(self) -> Option<Lowered<MySql>> {
+            Some(Lowered::Expr(Box::new(self)))
+        }
+    }
+
+    /// The vector builds as a list of values. MySQL has no array columns,
+    /// so only a bound `Vector` may contain the item.
+    impl<T, I> Build<MySql> for Contains<Vector<T>, I>
+    where
+        Vector<T>: Lower<MySql> + Build<MySql> + VectorTyped<MySql, VectorUnit<T>>,
+        I: Lower<MySql> + Build<MySql> + ScalarTyped<MySql, VectorUnit<T>>,
+    {
+        fn build(&self, builder: &mut QueryBuilder<MySql>) {
+            self.1.build(builder);
+            builder.push(" IN (");
+            self.0.build(builder);
+            builder.push(")");
+        }
+
+        #[cfg(feature = "testing")]
+        fn as_eval(&self) -> Option<&dyn crate::testing::Eval<MySql>> {
+            Some(self)
+        }
+    }
+}
+
+#[cfg(feature = "testing")]
+mod mock {
+    use super::*;
+    use crate::testing::{eval, Eval, Scope};
+    use crate::UrmResult;
+
+    impl<DB, V, I> Eval<DB> for Contains<V, I>
+    where
+        DB: Database,
+        V: Build<DB>,
+        I: Build<DB>,
+    {
+        fn eval(&self, scope: &Scope) -> UrmResult<serde_json::Value> {
+            let item = eval(&self.1, scope)?;
+
+            Ok(match eval(&self.0, scope)? {
+                serde_json::Value::Array(values) if !item.is_null() => {
+                    serde_json::Value::Bool(values.contains(&item))
+                }
+                _ => serde_json::Value::Null,
+            })
+        }
     }
 }
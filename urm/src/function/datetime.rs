@@ -0,0 +1,540 @@
+use crate::builder::Build;
+use crate::database::Database;
+use crate::lower::{Lower, Lowered};
+use crate::temporal::{DateField, Interval, Temporal};
+use crate::ty::{MapUnit, ScalarType, Type, Typed, Unit};
+
+/// The current date and time, in UTC, as a value of the type `T`.
+///
+/// `Now::<NaiveDateTime>::new()`
+pub struct Now<T>(std::marker::PhantomData<T>);
+
+impl<T> Now<T> {
+    pub fn new() -> Self {
+        Self(std::marker::PhantomData)
+    }
+}
+
+impl<T> Default for Now<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for Now<T> {
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+
+/// The operand truncated to the start of its year, month, day, hour, minute or second.
+#[derive(Clone)]
+pub struct DateTrunc<E>(pub DateField, pub E);
+
+/// A field of the operand as an integer, e.g. its year.
+#[derive(Clone)]
+pub struct Extract<E>(pub DateField, pub E);
+
+/// The operand moved forward in time by an interval.
+#[derive(Clone)]
+pub struct AddInterval<E>(pub E, pub Interval);
+
+/// The operand moved backward in time by an interval.
+#[derive(Clone)]
+pub struct SubInterval<E>(pub E, pub Interval);
+
+impl<DB: Database, T: Temporal> Typed<DB> for Now<T> {
+    type Ty = Unit<T>;
+}
+
+impl<DB: Database, T: Temporal> Lower<DB> for Now<T>
+where
+    Self: Build<DB>,
+{
+    fn lower(self) -> Option<Lowered<DB>> {
+        Some(Lowered::Expr(Box::new(self)))
+    }
+}
+
+/// Implement `Typed` and `Lower` for a function of one temporal operand, having the type `$ty`.
+macro_rules! temporal_function {
+    ($ident:ident, $operand:ident => $ty:ty) => {
+        impl<DB, $operand> Typed<DB> for $ident<$operand>
+        where
+            DB: Database,
+            $operand: Lower<DB> + Build<DB>,
+            $operand::Ty: ScalarType + MapUnit<i64>,
+            <$operand::Ty as Type>::Unit: Temporal,
+        {
+            type Ty = $ty;
+        }
+
+        impl<DB, $operand> Lower<DB> for $ident<$operand>
+        where
+            DB: Database,
+            $operand: Lower<DB> + Build<DB>,
+            $operand::Ty: ScalarType + MapUnit<i64>,
+            <$operand::Ty as Type>::Unit: Temporal,
+            Self: Build<DB>,
+        {
+            fn lower(self) -> Option<Lowered<DB>> {
+                Some(Lowered::Expr(Box::new(self)))
+            }
+        }
+    };
+}
+
+temporal_function!(DateTrunc, E => E::Ty);
+temporal_function!(Extract, E => <E::Ty as MapUnit<i64>>::Ty);
+temporal_function!(AddInterval, E => E::Ty);
+temporal_function!(SubInterval, E => E::Ty);
+
+///
+/// Implement `Build` of the temporal functions in the database `$db`,
+/// using a function of the dialect for each of them:
+///
+/// * `now(builder, time_of_day, offset)`
+/// * `trunc(builder, operand, field, time_of_day)`
+/// * `extract(builder, operand, field)`
+/// * `add_interval(builder, operand, interval, time_of_day)`
+///
+#[allow(unused_macros)]
+macro_rules! temporal_builds {
+    ($db:ty, $now:path, $trunc:path, $extract:path, $add_interval:path) => {
+        impl<T: Temporal> Build<$db> for Now<T> {
+            fn build(&self, builder: &mut QueryBuilder<$db>) {
+                $now(builder, T::TIME_OF_DAY, T::OFFSET);
+            }
+
+            #[cfg(feature = "testing")]
+            fn as_eval(&self) -> Option<&dyn crate::testing::Eval<$db>> {
+                Some(self)
+            }
+        }
+
+        impl<E> Build<$db> for DateTrunc<E>
+        where
+            E: Lower<$db> + Build<$db>,
+            E::Ty: ScalarType + MapUnit<i64>,
+            <E::Ty as Type>::Unit: Temporal,
+        {
+            fn build(&self, builder: &mut QueryBuilder<$db>) {
+                $trunc(
+                    builder,
+                    &self.1,
+                    self.0,
+                    <<E::Ty as Type>::Unit as Temporal>::TIME_OF_DAY,
+                );
+            }
+
+            #[cfg(feature = "testing")]
+            fn as_eval(&self) -> Option<&dyn crate::testing::Eval<$db>> {
+                Some(self)
+            }
+        }
+
+        impl<E> Build<$db> for Extract<E>
+        where
+            E: Lower<$db> + Build<$db>,
+            E::Ty: ScalarType + MapUnit<i64>,
+            <E::Ty as Type>::Unit: Temporal,
+        {
+            fn build(&self, builder: &mut QueryBuilder<$db>) {
+                $extract(builder, &self.1, self.0);
+            }
+
+            #[cfg(feature = "testing")]
+            fn as_eval(&self) -> Option<&dyn crate::testing::Eval<$db>> {
+                Some(self)
+            }
+        }
+
+        impl<E> Build<$db> for AddInterval<E>
+        where
+            E: Lower<$db> + Build<$db>,
+            E::Ty: ScalarType + MapUnit<i64>,
+            <E::Ty as Type>::Unit: Temporal,
+        {
+            fn build(&self, builder: &mut QueryBuilder<$db>) {
+                $add_interval(
+                    builder,
+                    &self.0,
+                    self.1,
+                    <<E::Ty as Type>::Unit as Temporal>::TIME_OF_DAY,
+                );
+            }
+
+            #[cfg(feature = "testing")]
+            fn as_eval(&self) -> Option<&dyn crate::testing::Eval<$db>> {
+                Some(self)
+            }
+        }
+
+        impl<E> Build<$db> for SubInterval<E>
+        where
+            E: Lower<$db> + Build<$db>,
+            E::Ty: ScalarType + MapUnit<i64>,
+            <E::Ty as Type>::Unit: Temporal,
+        {
+            fn build(&self, builder: &mut QueryBuilder<$db>) {
+                $add_interval(
+                    builder,
+                    &self.0,
+                    -self.1,
+                    <<E::Ty as Type>::Unit as Temporal>::TIME_OF_DAY,
+                );
+            }
+
+            #[cfg(feature = "testing")]
+            fn as_eval(&self) -> Option<&dyn crate::testing::Eval<$db>> {
+                Some(self)
+            }
+        }
+    };
+}
+
+/// The parts of an interval which are not zero, by the name of their unit.
+#[cfg(any(feature = "postgres", feature = "sqlite", feature = "mysql"))]
+fn interval_parts(interval: Interval) -> Vec<(i64, &'static str)> {
+    let parts: Vec<_> = vec![
+        (interval.months as i64, "MONTH"),
+        (interval.days as i64, "DAY"),
+        (interval.seconds, "SECOND"),
+    ]
+    .into_iter()
+    .filter(|(amount, _)| *amount != 0)
+    .collect();
+
+    match parts.is_empty() {
+        true => vec![(0, "SECOND")],
+        false => parts,
+    }
+}
+
+#[cfg(feature = "postgres")]
+mod postgres {
+    use super::*;
+    use crate::builder::QueryBuilder;
+
+    /// Build `now` in standard SQL, where `CURRENT_TIMESTAMP` has the time zone of the session.
+    fn build_now<DB: Database>(builder: &mut QueryBuilder<DB>, time_of_day: bool, offset: bool) {
+        builder.push(match (time_of_day, offset) {
+            (false, _) => "CURRENT_DATE",
+            (true, false) => "(CURRENT_TIMESTAMP AT TIME ZONE 'UTC')",
+            (true, true) => "CURRENT_TIMESTAMP",
+        });
+    }
+
+    /// Build `DateTrunc` using `date_trunc`, which yields a timestamp even of a date.
+    fn build_trunc<DB: Database>(
+        builder: &mut QueryBuilder<DB>,
+        expr: &dyn Build<DB>,
+        field: DateField,
+        time_of_day: bool,
+    ) {
+        if !time_of_day {
+            builder.push("CAST(");
+        }
+        builder.push("date_trunc('");
+        builder.push(field.name());
+        builder.push("', ");
+        expr.build(builder);
+        builder.push(")");
+        if !time_of_day {
+            builder.push(" AS DATE)");
+        }
+    }
+
+    /// Build `Extract` of whole numbers, where `EXTRACT` yields seconds with their fraction.
+    fn build_extract<DB: Database>(
+        builder: &mut QueryBuilder<DB>,
+        expr: &dyn Build<DB>,
+        field: DateField,
+    ) {
+        builder.push("CAST(FLOOR(EXTRACT(");
+        builder.push(field.name());
+        builder.push(" FROM ");
+        expr.build(builder);
+        builder.push(")) AS BIGINT)");
+    }
+
+    /// Build `AddInterval` using an interval literal, where adding it to a date yields a timestamp.
+    fn build_add_interval<DB: Database>(
+        builder: &mut QueryBuilder<DB>,
+        expr: &dyn Build<DB>,
+        interval: Interval,
+        time_of_day: bool,
+    ) {
+        if !time_of_day {
+            builder.push("CAST(");
+        }
+        builder.push("(");
+        expr.build(builder);
+        builder.push(" + INTERVAL '");
+        for (index, (amount, unit)) in interval_parts(interval).into_iter().enumerate() {
+            if index > 0 {
+                builder.push(" ");
+            }
+            builder.push(&format!("{} {}S", amount, unit));
+        }
+        builder.push("')");
+        if !time_of_day {
+            builder.push(" AS DATE)");
+        }
+    }
+
+    temporal_builds!(
+        crate::database::Postgres,
+        build_now,
+        build_trunc,
+        build_extract,
+        build_add_interval
+    );
+}
+
+/// SQLite stores dates and times as text, which its functions parse and format.
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use super::*;
+    use crate::builder::QueryBuilder;
+    use crate::database::Sqlite;
+
+    /// SQLite's `CURRENT_TIMESTAMP` is in UTC.
+    fn build_now(builder: &mut QueryBuilder<Sqlite>, time_of_day: bool, _offset: bool) {
+        builder.push(match time_of_day {
+            true => "CURRENT_TIMESTAMP",
+            false => "CURRENT_DATE",
+        });
+    }
+
+    fn build_trunc(
+        builder: &mut QueryBuilder<Sqlite>,
+        expr: &dyn Build<Sqlite>,
+        field: DateField,
+        time_of_day: bool,
+    ) {
+        let format = match field {
+            DateField::Year => "%Y-01-01 00:00:00",
+            DateField::Month => "%Y-%m-01 00:00:00",
+            DateField::Day => "%Y-%m-%d 00:00:00",
+            DateField::Hour => "%Y-%m-%d %H:00:00",
+            DateField::Minute => "%Y-%m-%d %H:%M:00",
+            DateField::Second => "%Y-%m-%d %H:%M:%S",
+        };
+
+        builder.push("strftime('");
+        builder.push(if time_of_day { format } else { &format[..8] });
+        builder.push("', ");
+        expr.build(builder);
+        builder.push(")");
+    }
+
+    fn build_extract(
+        builder: &mut QueryBuilder<Sqlite>,
+        expr: &dyn Build<Sqlite>,
+        field: DateField,
+    ) {
+        builder.push("CAST(strftime('");
+        builder.push(match field {
+            DateField::Year => "%Y",
+            DateField::Month => "%m",
+            DateField::Day => "%d",
+            DateField::Hour => "%H",
+            DateField::Minute => "%M",
+            DateField::Second => "%S",
+        });
+        builder.push("', ");
+        expr.build(builder);
+        builder.push(") AS INTEGER)");
+    }
+
+    /// Unlike Postgres and MySQL, SQLite lets a day overflow into the next month
+    /// when adding months, e.g. January 31st plus one month is March 3rd.
+    fn build_add_interval(
+        builder: &mut QueryBuilder<Sqlite>,
+        expr: &dyn Build<Sqlite>,
+        interval: Interval,
+        time_of_day: bool,
+    ) {
+        builder.push(if time_of_day { "datetime(" } else { "date(" });
+        expr.build(builder);
+        for (amount, unit) in interval_parts(interval) {
+            builder.push(&format!(", '{:+} {}S'", amount, unit));
+        }
+        builder.push(")");
+    }
+
+    temporal_builds!(
+        Sqlite,
+        build_now,
+        build_trunc,
+        build_extract,
+        build_add_interval
+    );
+}
+
+#[cfg(feature = "mysql")]
+mod mysql {
+    use super::*;
+    use crate::builder::QueryBuilder;
+    use crate::database::MySql;
+
+    fn build_now(builder: &mut QueryBuilder<MySql>, time_of_day: bool, _offset: bool) {
+        builder.push(match time_of_day {
+            true => "UTC_TIMESTAMP()",
+            false => "UTC_DATE()",
+        });
+    }
+
+    fn build_trunc(
+        builder: &mut QueryBuilder<MySql>,
+        expr: &dyn Build<MySql>,
+        field: DateField,
+        time_of_day: bool,
+    ) {
+        let format = match field {
+            DateField::Year => "%Y-01-01 00:00:00",
+            DateField::Month => "%Y-%m-01 00:00:00",
+            DateField::Day => "%Y-%m-%d 00:00:00",
+            DateField::Hour => "%Y-%m-%d %H:00:00",
+            DateField::Minute => "%Y-%m-%d %H:%i:00",
+            DateField::Second => "%Y-%m-%d %H:%i:%s",
+        };
+
+        builder.push("CAST(DATE_FORMAT(");
+        expr.build(builder);
+        builder.push(", '");
+        builder.push(format);
+        builder.push(if time_of_day {
+            "') AS DATETIME)"
+        } else {
+            "') AS DATE)"
+        });
+    }
+
+    fn build_extract(builder: &mut QueryBuilder<MySql>, expr: &dyn Build<MySql>, field: DateField) {
+        builder.push("EXTRACT(");
+        builder.push(field.name());
+        builder.push(" FROM ");
+        expr.build(builder);
+        builder.push(")");
+    }
+
+    fn build_add_interval(
+        builder: &mut QueryBuilder<MySql>,
+        expr: &dyn Build<MySql>,
+        interval: Interval,
+        time_of_day: bool,
+    ) {
+        if !time_of_day {
+            builder.push("CAST(");
+        }
+        builder.push("(");
+        expr.build(builder);
+        for (amount, unit) in interval_parts(interval) {
+            builder.push(&format!(" + INTERVAL {} {}", amount, unit));
+        }
+        builder.push(")");
+        if !time_of_day {
+            builder.push(" AS DATE)");
+        }
+    }
+
+    temporal_builds!(
+        MySql,
+        build_now,
+        build_trunc,
+        build_extract,
+        build_add_interval
+    );
+}
+
+#[cfg(feature = "testing")]
+mod mock {
+    use super::*;
+    use crate::testing::{eval, Eval, Scope};
+    use crate::{UrmError, UrmResult};
+    use serde_json::Value;
+
+    impl<DB: Database, T: Temporal> Eval<DB> for Now<T> {
+        fn eval(&self, _scope: &Scope) -> UrmResult<Value> {
+            Ok(to_value(Some(T::now())))
+        }
+    }
+
+    impl<DB, E> Eval<DB> for DateTrunc<E>
+    where
+        DB: Database,
+        E: Typed<DB> + Build<DB>,
+        <E::Ty as Type>::Unit: Temporal,
+    {
+        fn eval(&self, scope: &Scope) -> UrmResult<Value> {
+            let field = self.0;
+            eval_temporal(eval(&self.1, scope)?, |value: <E::Ty as Type>::Unit| {
+                to_value(value.truncate(field))
+            })
+        }
+    }
+
+    impl<DB, E> Eval<DB> for Extract<E>
+    where
+        DB: Database,
+        E: Typed<DB> + Build<DB>,
+        <E::Ty as Type>::Unit: Temporal,
+    {
+        fn eval(&self, scope: &Scope) -> UrmResult<Value> {
+            let field = self.0;
+            eval_temporal(eval(&self.1, scope)?, |value: <E::Ty as Type>::Unit| {
+                value.extract(field).into()
+            })
+        }
+    }
+
+    impl<DB, E> Eval<DB> for AddInterval<E>
+    where
+        DB: Database,
+        E: Typed<DB> + Build<DB>,
+        <E::Ty as Type>::Unit: Temporal,
+    {
+        fn eval(&self, scope: &Scope) -> UrmResult<Value> {
+            let interval = self.1;
+            eval_temporal(eval(&self.0, scope)?, |value: <E::Ty as Type>::Unit| {
+                to_value(value.add_interval(interval))
+            })
+        }
+    }
+
+    impl<DB, E> Eval<DB> for SubInterval<E>
+    where
+        DB: Database,
+        E: Typed<DB> + Build<DB>,
+        <E::Ty as Type>::Unit: Temporal,
+    {
+        fn eval(&self, scope: &Scope) -> UrmResult<Value> {
+            let interval = -self.1;
+            eval_temporal(eval(&self.0, scope)?, |value: <E::Ty as Type>::Unit| {
+                to_value(value.add_interval(interval))
+            })
+        }
+    }
+
+    /// Evaluate a temporal function on a value of the type `T`, given as JSON or as text.
+    fn eval_temporal<T: Temporal>(value: Value, func: impl Fn(T) -> Value) -> UrmResult<Value> {
+        if value.is_null() {
+            return Ok(Value::Null);
+        }
+
+        serde_json::from_value(value.clone())
+            .ok()
+            .or_else(|| value.as_str().and_then(T::parse))
+            .map(func)
+            .ok_or_else(|| UrmError::Eval(format!("expected a date or time, found {}", value)))
+    }
+
+    /// The JSON of a value, which is null when it is out of range.
+    fn to_value<T: Temporal>(value: Option<T>) -> Value {
+        value
+            .and_then(|value| serde_json::to_value(value).ok())
+            .unwrap_or(Value::Null)
+    }
+}
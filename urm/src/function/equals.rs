@@ -4,6 +4,7 @@ use crate::lower::{Lower, Lowered};
 use crate::ty::{Nullable, Type, Typed};
 
 /// Binary function that tests equality between two given operands
+#[derive(Clone)]
 pub struct Equals<L, R>(pub L, pub R);
 
 impl<DB, L, R> Typed<DB> for Equals<L, R>
@@ -40,4 +41,26 @@ where
         builder.push(" = ");
         self.1.build(builder);
     }
+
+    #[cfg(feature = "testing")]
+    fn as_eval(&self) -> Option<&dyn crate::testing::Eval<DB>> {
+        Some(self)
+    }
+}
+
+#[cfg(feature = "testing")]
+impl<DB, L, R> crate::testing::Eval<DB> for Equals<L, R>
+where
+    DB: Database,
+    L: Build<DB>,
+    R: Build<DB>,
+{
+    fn eval(&self, scope: &crate::testing::Scope) -> crate::UrmResult<serde_json::Value> {
+        use crate::testing::eval;
+
+        Ok(match (eval(&self.0, scope)?, eval(&self.1, scope)?) {
+            (serde_json::Value::Null, _) | (_, serde_json::Value::Null) => serde_json::Value::Null,
+            (left, right) => serde_json::Value::Bool(left == right),
+        })
+    }
 }
@@ -1,5 +1,23 @@
+mod arithmetic;
+mod array;
+mod case;
+mod cast;
+mod coalesce;
+mod concat;
 mod contains;
+#[cfg(any(feature = "chrono", feature = "time"))]
+mod datetime;
 mod equals;
+mod text;
 
+pub use arithmetic::{Add, Div, Mul, Neg, Sub};
+pub use array::{ArrayContains, ArrayLength, Overlaps};
+pub use case::{Branches, Case, CaseOtherwise, CaseWhen, ElseWhen, Otherwise, When};
+pub use cast::{Cast, SqlType};
+pub use coalesce::Coalesce;
+pub use concat::Concat;
 pub use contains::Contains;
+#[cfg(any(feature = "chrono", feature = "time"))]
+pub use datetime::{AddInterval, DateTrunc, Extract, Now, SubInterval};
 pub use equals::Equals;
+pub use text::{Length, Lower, Trim, Upper};
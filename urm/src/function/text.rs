@@ -0,0 +1,215 @@
+use crate::builder::{Build, QueryBuilder};
+use crate::database::Database;
+use crate::lower::{self, Lowered};
+use crate::ty::{MapUnit, ScalarTyped, Typed};
+
+/// Define a function of one text operand, yielding text, spelled the same in every dialect.
+macro_rules! text_function {
+    ($(#[$meta:meta])* $ident:ident, $sql:literal, $eval:expr) => {
+        $(#[$meta])*
+        #[derive(Clone)]
+        pub struct $ident<E>(pub E);
+
+        impl<DB, E> Typed<DB> for $ident<E>
+        where
+            DB: Database,
+            E: lower::Lower<DB> + Build<DB> + ScalarTyped<DB, String>,
+        {
+            type Ty = E::Ty;
+        }
+
+        impl<DB, E> lower::Lower<DB> for $ident<E>
+        where
+            DB: Database,
+            E: lower::Lower<DB> + Build<DB> + ScalarTyped<DB, String>,
+        {
+            fn lower(self) -> Option<Lowered<DB>> {
+                Some(Lowered::Expr(Box::new(self)))
+            }
+        }
+
+        impl<DB, E> Build<DB> for $ident<E>
+        where
+            DB: Database,
+            E: lower::Lower<DB> + Build<DB> + ScalarTyped<DB, String>,
+        {
+            fn build(&self, builder: &mut QueryBuilder<DB>) {
+                builder.push(concat!($sql, "("));
+                self.0.build(builder);
+                builder.push(")");
+            }
+
+            #[cfg(feature = "testing")]
+            fn as_eval(&self) -> Option<&dyn crate::testing::Eval<DB>> {
+                Some(self)
+            }
+        }
+
+        #[cfg(feature = "testing")]
+        impl<DB, E> crate::testing::Eval<DB> for $ident<E>
+        where
+            DB: Database,
+            E: Build<DB>,
+        {
+            fn eval(
+                &self,
+                scope: &crate::testing::Scope,
+            ) -> crate::UrmResult<serde_json::Value> {
+                mock::eval_text(crate::testing::eval(&self.0, scope)?, $eval)
+            }
+        }
+    };
+}
+
+text_function!(
+    /// The text operand in lower case.
+    Lower,
+    "LOWER",
+    |text: &str| text.to_lowercase().into()
+);
+
+text_function!(
+    /// The text operand in upper case.
+    Upper,
+    "UPPER",
+    |text: &str| text.to_uppercase().into()
+);
+
+text_function!(
+    /// The text operand without leading and trailing spaces.
+    Trim,
+    "TRIM",
+    |text: &str| text.trim_matches(' ').into()
+);
+
+/// The number of characters in the text operand.
+#[derive(Clone)]
+pub struct Length<E>(pub E);
+
+impl<DB, E> Typed<DB> for Length<E>
+where
+    DB: Database,
+    E: lower::Lower<DB> + Build<DB> + ScalarTyped<DB, String>,
+    E::Ty: MapUnit<i64>,
+{
+    type Ty = <E::Ty as MapUnit<i64>>::Ty;
+}
+
+impl<DB, E> lower::Lower<DB> for Length<E>
+where
+    DB: Database,
+    E: lower::Lower<DB> + Build<DB> + ScalarTyped<DB, String>,
+    E::Ty: MapUnit<i64>,
+    Self: Build<DB>,
+{
+    fn lower(self) -> Option<Lowered<DB>> {
+        Some(Lowered::Expr(Box::new(self)))
+    }
+}
+
+/// Build `Length` as a call to the function counting characters, not bytes.
+#[cfg(any(feature = "postgres", feature = "sqlite", feature = "mysql"))]
+fn build_length<DB: Database, E: Build<DB>>(
+    function: &str,
+    expr: &E,
+    builder: &mut QueryBuilder<DB>,
+) {
+    builder.push(function);
+    builder.push("(");
+    expr.build(builder);
+    builder.push(")");
+}
+
+#[cfg(feature = "postgres")]
+mod postgres {
+    use super::*;
+    use crate::database::Postgres;
+
+    impl<E> Build<Postgres> for Length<E>
+    where
+        E: lower::Lower<Postgres> + Build<Postgres> + ScalarTyped<Postgres, String>,
+        E::Ty: MapUnit<i64>,
+    {
+        fn build(&self, builder: &mut QueryBuilder<Postgres>) {
+            build_length("char_length", &self.0, builder);
+        }
+
+        #[cfg(feature = "testing")]
+        fn as_eval(&self) -> Option<&dyn crate::testing::Eval<Postgres>> {
+            Some(self)
+        }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use super::*;
+    use crate::database::Sqlite;
+
+    /// SQLite's `length` counts characters of text.
+    impl<E> Build<Sqlite> for Length<E>
+    where
+        E: lower::Lower<Sqlite> + Build<Sqlite> + ScalarTyped<Sqlite, String>,
+        E::Ty: MapUnit<i64>,
+    {
+        fn build(&self, builder: &mut QueryBuilder<Sqlite>) {
+            build_length("length", &self.0, builder);
+        }
+
+        #[cfg(feature = "testing")]
+        fn as_eval(&self) -> Option<&dyn crate::testing::Eval<Sqlite>> {
+            Some(self)
+        }
+    }
+}
+
+#[cfg(feature = "mysql")]
+mod mysql {
+    use super::*;
+    use crate::database::MySql;
+
+    /// MySQL's `LENGTH` counts bytes, `CHAR_LENGTH` counts characters.
+    impl<E> Build<MySql> for Length<E>
+    where
+        E: lower::Lower<MySql> + Build<MySql> + ScalarTyped<MySql, String>,
+        E::Ty: MapUnit<i64>,
+    {
+        fn build(&self, builder: &mut QueryBuilder<MySql>) {
+            build_length("CHAR_LENGTH", &self.0, builder);
+        }
+
+        #[cfg(feature = "testing")]
+        fn as_eval(&self) -> Option<&dyn crate::testing::Eval<MySql>> {
+            Some(self)
+        }
+    }
+}
+
+#[cfg(feature = "testing")]
+mod mock {
+    use super::*;
+    use crate::testing::{eval, Eval, Scope};
+    use crate::{UrmError, UrmResult};
+
+    impl<DB, E> Eval<DB> for Length<E>
+    where
+        DB: Database,
+        E: Build<DB>,
+    {
+        fn eval(&self, scope: &Scope) -> UrmResult<serde_json::Value> {
+            eval_text(eval(&self.0, scope)?, |text| text.chars().count().into())
+        }
+    }
+
+    /// Evaluate a text function in memory, where null text yields null.
+    pub(super) fn eval_text(
+        value: serde_json::Value,
+        func: impl Fn(&str) -> serde_json::Value,
+    ) -> UrmResult<serde_json::Value> {
+        match value {
+            serde_json::Value::Null => Ok(serde_json::Value::Null),
+            serde_json::Value::String(text) => Ok(func(&text)),
+            value => Err(UrmError::Eval(format!("expected text, found {}", value))),
+        }
+    }
+}
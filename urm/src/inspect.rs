@@ -0,0 +1,103 @@
+//!
+//! A read-only view of probed queries, for tests, logs and tooling.
+//!
+//! Every view is serializable to JSON.
+//!
+
+use parking_lot::Mutex;
+use std::sync::Arc;
+
+use crate::quantify::Quantity;
+
+///
+/// The inspection of a query built by probing a root select.
+///
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Inspection {
+    /// The root select.
+    pub select: SelectInspection,
+
+    /// The SQL of the query, with argument placeholders.
+    pub sql: String,
+
+    /// The SQL of the query, with arguments written as literals.
+    pub inlined_sql: String,
+
+    /// The arguments bound to the placeholders of `sql`.
+    pub arguments: Vec<serde_json::Value>,
+}
+
+///
+/// A select from one table, the root of a query or a nested foreign select.
+///
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct SelectInspection {
+    pub table: String,
+
+    /// The alias of the table within the query, e.g. `a0`.
+    pub alias: String,
+
+    pub projection: Vec<FieldInspection>,
+
+    pub filter: Option<Fragment>,
+
+    pub limit: Option<usize>,
+
+    pub offset: usize,
+}
+
+///
+/// One projected field of a select, keyed by its `ProjectionKey`.
+///
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FieldInspection {
+    Primitive {
+        key: String,
+        expr: Fragment,
+    },
+    Foreign {
+        key: String,
+        quantity: Quantity,
+        select: Box<SelectInspection>,
+    },
+}
+
+///
+/// An SQL expression, rendered on its own.
+///
+/// Placeholders are numbered from the start of the fragment.
+///
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Fragment {
+    pub sql: String,
+    pub inlined_sql: String,
+    pub arguments: Vec<serde_json::Value>,
+}
+
+///
+/// Records the inspection of every query probed by a root select.
+///
+/// When using async-graphql, register this as schema data.
+/// Recording happens whether or not a database connection is present,
+/// so without a connection queries are inspected without being executed.
+///
+#[derive(Clone, Default)]
+pub struct Inspector {
+    inspections: Arc<Mutex<Vec<Inspection>>>,
+}
+
+impl Inspector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn record(&self, inspection: Inspection) {
+        self.inspections.lock().push(inspection);
+    }
+
+    /// The inspections recorded so far, in order of probing.
+    pub fn inspections(&self) -> Vec<Inspection> {
+        self.inspections.lock().clone()
+    }
+}
@@ -12,7 +12,12 @@
 //! ```
 //!
 
-use async_trait::*;
+#[doc(hidden)]
+pub use async_trait::async_trait;
+#[doc(hidden)]
+pub use serde;
+#[doc(hidden)]
+pub use serde_json;
 
 pub use urm_macros::*;
 
@@ -20,9 +25,11 @@ pub mod builder;
 pub mod column;
 pub mod database;
 pub mod expr;
+pub mod fetch;
 pub mod filter;
 pub mod foreign;
 pub mod function;
+pub mod inspect;
 pub mod logic;
 pub mod lower;
 pub mod predicate;
@@ -30,6 +37,10 @@ pub mod prelude;
 pub mod probe;
 pub mod project;
 pub mod quantify;
+#[cfg(any(feature = "chrono", feature = "time"))]
+pub mod temporal;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod ty;
 pub mod value;
 
@@ -51,68 +62,177 @@ pub trait Instance {
     fn instance() -> &'static Self;
 }
 
-pub struct Select<T: Table, W> {
+///
+/// A root select from the table `T`, filtered by `W`.
+///
+/// `Q` is the quantification of the selected rows, which is
+/// `quantify::AsVec` for a plain select, or `quantify::AsOption`
+/// for a select yielding at most one row.
+///
+pub struct Select<T: Table, W, Q = quantify::AsVec> {
     table: std::marker::PhantomData<T>,
+    quantify: std::marker::PhantomData<Q>,
     filter: W,
+    range: Box<dyn lower::BuildRange<T::DB>>,
 }
 
-impl<T, W> Select<T, W>
+impl<T, W, Q> Select<T, W, Q>
 where
     T: Table + Instance,
 {
-    pub fn filter<W2>(self, f: W2) -> Select<T, logic::And<W, W2>>
+    pub fn filter<W2>(self, f: W2) -> Select<T, logic::And<W, W2>, Q>
     where
         W2: lower::Lower<T::DB> + ty::ScalarTyped<T::DB, bool>,
     {
         Select {
             table: self.table,
+            quantify: self.quantify,
             filter: logic::And(self.filter, f),
+            range: self.range,
+        }
+    }
+
+    /// Project each selected row with `projection`, for querying without probing.
+    ///
+    /// `projection` is a `ProjectNode`, like a `Column`, a foreign projection made with
+    /// `foreign::ProjectForeign::project`, or a tuple of those.
+    pub fn project<M>(self, projection: M) -> fetch::Projected<T, W, Q, M>
+    where
+        M: ProjectNode<T> + Clone,
+    {
+        fetch::Projected {
+            select: self,
+            projection,
+        }
+    }
+
+    /// Turn this into a select that yields at most one row,
+    /// e.g. when filtering on a unique key.
+    pub fn single(self) -> Select<T, W, quantify::AsOption> {
+        Select {
+            table: self.table,
+            quantify: std::marker::PhantomData,
+            filter: self.filter,
+            range: self.range,
         }
     }
 
     /// Perform probing for the select, thus building a suitable query
-    /// to send to the database.
+    /// to send to the database, then execute it and deserialize the results.
+    ///
+    /// The database is looked up as a `database::Connection` in the schema data.
+    /// If an `inspect::Inspector` is also found there, the query is recorded before execution.
     #[cfg(feature = "async_graphql")]
     pub async fn probe_with<F, U>(
         self,
         func: F,
         ctx: &async_graphql::Context<'_>,
-    ) -> UrmResult<Vec<U>>
+    ) -> UrmResult<<Q as quantify::Quantify<U>>::Output>
     where
         F: Fn(Node<T>) -> U,
         U: Probe + async_graphql::ContainerType,
         W: lower::Lower<T::DB> + ty::ScalarTyped<T::DB, bool>,
+        Q: quantify::Quantify<U>,
     {
-        let table = T::instance();
-        let (engine, probing) = engine::Engine::new_select(table, self.filter.lower_where());
-        let node = Node::<T>::new_probe(probing);
+        self.probe_with_driver(func, &probe::async_graphql::ContextDriver::new(ctx))
+            .await
+    }
 
-        let container = func(node);
+    /// Like `probe_with`, but for juniper, resolving the root field of a query
+    /// executed by `probe::juniper::execute`.
+    ///
+    /// The fields selected on the container are probed by running their juniper resolvers
+    /// in the first pass, which yields no rows. The query is executed between the passes,
+    /// and its rows are deserialized in the second pass.
+    /// The juniper context provides the `probe::Data` holding the `database::Connection`.
+    #[cfg(feature = "juniper")]
+    pub fn probe_with_executor<F, U, CtxT, S>(
+        self,
+        func: F,
+        executor: &juniper::Executor<'_, CtxT, S>,
+    ) -> UrmResult<<Q as quantify::Quantify<U>>::Output>
+    where
+        F: Fn(Node<T>) -> U,
+        U: Probe + juniper::GraphQLType<S, Context = CtxT, TypeInfo = ()>,
+        CtxT: AsRef<probe::Data>,
+        S: juniper::ScalarValue,
+        for<'b> &'b S: juniper::ScalarRefValue<'b>,
+        W: lower::Lower<T::DB> + ty::ScalarTyped<T::DB, bool>,
+        Q: quantify::Quantify<U>,
+    {
+        let table = T::instance();
+        let key = probe::juniper::response_key(executor);
 
-        probe::probe_container(&container, ctx);
+        match probe::juniper::root_pass(&key)? {
+            probe::juniper::RootPass::Probe => {
+                let (engine, probing) =
+                    engine::Engine::new_select(table, self.filter.lower_where(), self.range);
+                let container = func(Node::<T>::new_probe(probing.clone()));
 
-        {
-            let lock = engine.query.lock();
-            let mut sql = String::new();
-            let mut builder = builder::QueryBuilder::new(table, &mut sql);
+                let failures = probe::juniper::probe_root(&key, executor, &container, &probing);
+                let data = executor.context().as_ref();
+                let connection = data.get::<database::Connection<T::DB>>().cloned();
+                let inspector = data.get::<inspect::Inspector>().cloned();
 
-            lock.build_query(&mut builder);
+                probe::juniper::fetch_later(key, async move {
+                    execute(&engine, failures, connection.as_ref(), inspector.as_ref()).await
+                });
 
-            println!("{}", sql);
+                // The response of the probing pass is discarded
+                Q::quantify(vec![]).map_err(|_| UrmError::NotReady)
+            }
+            probe::juniper::RootPass::Deserialize(rows) => {
+                let units = rows?
+                    .into_iter()
+                    .map(|row| func(Node::new_deserialize(row)))
+                    .collect();
 
-            Err(UrmError::DebugSelect(sql))
+                Q::quantify(units).map_err(|rows| UrmError::quantity::<Q>(table.name(), rows))
+            }
         }
+    }
+
+    /// Like `probe_with`, but walking the selection using any `probe::ProbeDriver`.
+    ///
+    /// The `database::Connection` and `inspect::Inspector` are looked up in the driver's data.
+    pub async fn probe_with_driver<F, U, D>(
+        self,
+        func: F,
+        driver: &D,
+    ) -> UrmResult<<Q as quantify::Quantify<U>>::Output>
+    where
+        F: Fn(Node<T>) -> U,
+        U: Probe + Sync,
+        D: probe::ProbeDriver<U>,
+        W: lower::Lower<T::DB> + ty::ScalarTyped<T::DB, bool>,
+        Q: quantify::Quantify<U>,
+    {
+        let table = T::instance();
+        let (engine, probing) =
+            engine::Engine::new_select(table, self.filter.lower_where(), self.range);
+        let node = Node::<T>::new_probe(probing.clone());
 
-        /*
-        let query_engine = engine.query.clone();
-        let dbg = format!("{:?}", query_engine.lock());
+        let container = func(node);
 
-        Err(UrmError::DebugSelect(dbg))
-        */
+        let failures = driver.probe(&container, &probing).await;
+        let rows = execute(
+            &engine,
+            failures,
+            driver.data::<database::Connection<T::DB>>(),
+            driver.data::<inspect::Inspector>(),
+        )
+        .await?;
+
+        let units = rows
+            .into_iter()
+            .map(|row| func(Node::new_deserialize(row)))
+            .collect();
+
+        Q::quantify(units).map_err(|rows| UrmError::quantity::<Q>(table.name(), rows))
     }
 }
 
-impl<T, W, R> filter::Range<T::DB, R> for Select<T, W>
+impl<T, W, Q, R> filter::Range<T::DB, R> for Select<T, W, Q>
 where
     T: Table,
     R: lower::BuildRange<T::DB>,
@@ -120,8 +240,38 @@ where
     type Output = Self;
 
     fn range(self, range: R) -> Self::Output {
-        self
+        Select {
+            table: self.table,
+            quantify: self.quantify,
+            filter: self.filter,
+            range: Box::new(range),
+        }
+    }
+}
+
+/// Finish probing the query of `engine`, then execute it,
+/// returning the rows as JSON objects keyed by `ProjectionKey`.
+async fn execute<DB: database::Database>(
+    engine: &engine::Engine<DB>,
+    mut failures: Vec<probe::ProbeFailure>,
+    connection: Option<&database::Connection<DB>>,
+    inspector: Option<&inspect::Inspector>,
+) -> UrmResult<Vec<serde_json::Value>> {
+    failures.append(&mut engine.query.lock().probe_failures);
+    if !failures.is_empty() {
+        return Err(UrmError::Probe(failures));
     }
+
+    if let Some(inspector) = inspector {
+        inspector.record(engine.query.lock().inspect());
+    }
+
+    let query = engine.query.lock().build_query();
+
+    connection
+        .ok_or(UrmError::NoConnection)?
+        .fetch_rows(query)
+        .await
 }
 
 pub fn select<T>() -> Select<T, ty::Void<bool>>
@@ -130,7 +280,9 @@ where
 {
     Select {
         table: std::marker::PhantomData,
+        quantify: std::marker::PhantomData,
         filter: ty::Void::new(),
+        range: Box::new(()),
     }
 }
 
@@ -145,9 +297,10 @@ where
 /// If that node is a probing node, the produced future will *never* resolve to a ready value.
 /// If that node is a deserialization node, the produced future will try to yield the requested values.
 ///
-/// Therefore, in a probing infrastructure, `project` should always be invoked _as early as possible_
-/// at each graph node. In fact, the async infrastructure is treated as _synchronous_ in a probing
-/// setup, therefore `project` must be the first future that is awaited at each projection root.
+/// While probing, each resolver is driven until it reaches `project`, where it is parked.
+/// Other futures, like an authorization check or a data loader, may be awaited before `project`,
+/// but they are awaited once while probing and once again while deserializing.
+/// While probing, they must become ready before a deadline, see `probe::probe_field`.
 ///
 pub async fn project<T, P, M>(probe: &P, arg: M) -> UrmResult<M::Output>
 where
@@ -160,14 +313,129 @@ where
 
 #[derive(thiserror::Error, Debug, Clone)]
 pub enum UrmError {
-    #[error("Probe error")]
-    Probe,
+    /// Probing one or more GraphQL fields failed, so no query was executed.
+    #[error("Probe error at {}", display_probe_failures(.0))]
+    Probe(Vec<probe::ProbeFailure>),
+
+    /// A projected value could not be decoded into its expected `ty::Type`.
+    #[error("Could not decode {table}.{column} as {expected}: {message}")]
+    Decode {
+        table: &'static str,
+        column: String,
+        expected: &'static str,
+        message: String,
+    },
+
+    /// A select returned a number of rows not fitting its quantification.
+    #[error("Expected {expected} rows from {table}, found {rows}")]
+    Quantity {
+        table: &'static str,
+        expected: &'static str,
+        rows: usize,
+    },
+
+    #[error("Database error: {0}")]
+    Database(#[source] std::sync::Arc<sqlx::Error>),
+
+    /// An expression could not be evaluated by the `testing::MockDatabase`.
+    #[error("Evaluation error: {0}")]
+    Eval(String),
+
+    /// Fixture data given to the `testing::MockDatabase` is malformed.
+    #[error("Invalid fixture: {0}")]
+    Fixture(String),
+
+    #[error("No database connection")]
+    NoConnection,
+
+    /// A projection could not be resolved synchronously, because its node was being probed.
+    #[error("Projection is not ready")]
+    NotReady,
+}
+
+impl From<sqlx::Error> for UrmError {
+    fn from(error: sqlx::Error) -> Self {
+        Self::Database(std::sync::Arc::new(error))
+    }
+}
+
+impl UrmError {
+    /// A short machine-readable code for the kind of error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Probe(_) => "PROBE",
+            Self::Decode { .. } => "DECODE",
+            Self::Quantity { .. } => "QUANTITY",
+            Self::Database(_) => "DATABASE",
+            Self::Eval(_) => "EVAL",
+            Self::Fixture(_) => "FIXTURE",
+            Self::NoConnection => "NO_CONNECTION",
+            Self::NotReady => "NOT_READY",
+        }
+    }
+
+    pub(crate) fn quantity<Q>(table: &'static str, rows: usize) -> Self {
+        Self::Quantity {
+            table,
+            expected: std::any::type_name::<Q>(),
+            rows,
+        }
+    }
+}
 
-    #[error("Deserialization error")]
-    Deserialization,
+fn display_probe_failures(failures: &[probe::ProbeFailure]) -> String {
+    failures
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Converts into an error with a `code` extension, along with the context of the error.
+#[cfg(feature = "async_graphql")]
+impl async_graphql::ErrorExtensions for UrmError {
+    fn extend(self) -> async_graphql::Error {
+        let mut error = async_graphql::Error::new(self.to_string());
+        let extensions = error.extensions.get_or_insert_with(Default::default);
+
+        extensions.set("code", self.code());
+        match &self {
+            Self::Probe(failures) => {
+                if let Ok(failures) =
+                    serde_json::to_value(failures).and_then(async_graphql::Value::from_json)
+                {
+                    extensions.set("failures", failures);
+                }
+            }
+            Self::Decode {
+                table,
+                column,
+                expected,
+                ..
+            } => {
+                extensions.set("table", *table);
+                extensions.set("column", column.as_str());
+                extensions.set("expected", *expected);
+            }
+            Self::Quantity {
+                table,
+                expected,
+                rows,
+            } => {
+                extensions.set("table", *table);
+                extensions.set("expected", *expected);
+                extensions.set("rows", *rows as u64);
+            }
+            Self::Database(_)
+            | Self::Eval(_)
+            | Self::Fixture(_)
+            | Self::NoConnection
+            | Self::NotReady => {}
+        }
 
-    #[error("Debug select: {0}")]
-    DebugSelect(String),
+        error.source = Some(std::sync::Arc::new(self));
+        error
+    }
 }
 
 pub type UrmResult<T> = Result<T, UrmError>;
@@ -196,17 +464,40 @@ impl<T: Table> Node<T> {
         }
     }
 
-    pub(crate) fn new_deserialize() -> Self {
+    pub(crate) fn new_deserialize(row: serde_json::Value) -> Self {
         Self {
-            phase: Phase::Deserialize,
+            phase: Phase::Deserialize(row),
             table: std::marker::PhantomData,
         }
     }
+
+    /// Project one field of a `Project` struct out of this node.
+    ///
+    /// While probing, the projection is only probed and `None` is returned.
+    pub async fn project_field<P>(
+        &self,
+        projection: P,
+    ) -> UrmResult<Option<<P::Ty as ty::Type>::Output>>
+    where
+        P: project::ProjectFrom<Table = T>
+            + Typed<T::DB>
+            + project::ProjectAndProbe<T::DB>
+            + project::ProjectAndDeserialize<T::DB>,
+    {
+        match &self.phase {
+            Phase::Probe(probing) => {
+                projection.project_and_probe(probing).await?;
+                Ok(None)
+            }
+            Phase::Deserialize(row) => projection.project_and_deserialize(row).map(Some),
+        }
+    }
 }
 
 enum Phase<DB: database::Database> {
     Probe(engine::Probing<DB>),
-    Deserialize,
+    /// A row of the executed query, a JSON object keyed by `ProjectionKey`.
+    Deserialize(serde_json::Value),
 }
 
 ///
@@ -228,12 +519,47 @@ pub trait Probe {
     fn node(&self) -> &Node<Self::Table>;
 }
 
+///
+/// A struct projected field by field from a table, usually implemented with `#[derive(Project)]`:
+///
+/// ```ignore
+/// #[derive(urm::Project)]
+/// #[project(db::Publication)]
+/// struct Publication {
+///     #[column(db::Publication.title())]
+///     title: String,
+///     #[foreign(db::Publication.editions())]
+///     editions: Vec<Edition>,
+/// }
+///
+/// let publications: Vec<Publication> = urm::select::<db::Publication>()
+///     .project(Publication::projection())
+///     .fetch(&connection)
+///     .await?;
+/// ```
+///
+/// A field annotated with `#[foreign(..)]` is itself a `Project` struct, quantified
+/// like the foreign projection: a plain struct for `OneToOne`, a `Vec` for `OneToMany`.
+///
+#[async_trait]
+pub trait Project: Sized + Send + Sync + 'static {
+    type Table: Table;
+
+    /// Project every field out of `node`, yielding `None` while probing.
+    async fn project_fields(node: &Node<Self::Table>) -> UrmResult<Option<Self>>;
+
+    /// The `ProjectNode` projecting this struct.
+    fn projection() -> project::Projection<Self> {
+        project::Projection::new()
+    }
+}
+
 ///
 /// Project projectable types given a `Node<T>`.
 ///
 #[async_trait]
-pub trait ProjectNode<T: Table> {
-    type Output;
+pub trait ProjectNode<T: Table>: Send + Sync {
+    type Output: Send + Sync + 'static;
 
     async fn project_node(self, node: &Node<T>) -> UrmResult<Self::Output>;
 }
@@ -242,38 +568,91 @@ pub trait ProjectNode<T: Table> {
 impl<T, P> ProjectNode<T> for P
 where
     T: Table,
-    P: project::ProjectFrom<Table = T> + Typed<T::DB> + project::ProjectAndProbe<T::DB>,
+    P: project::ProjectFrom<Table = T>
+        + Typed<T::DB>
+        + project::ProjectAndProbe<T::DB>
+        + project::ProjectAndDeserialize<T::DB>,
 {
     type Output = <P::Ty as ty::Type>::Output;
 
     async fn project_node(self, node: &Node<T>) -> UrmResult<Self::Output> {
         match &node.phase {
             Phase::Probe(probing) => {
-                self.project_and_probe(probing)?;
-                never::never().await
+                self.project_and_probe(probing).await?;
+                probing.park().await
             }
-            Phase::Deserialize => Err(UrmError::Deserialization),
+            Phase::Deserialize(row) => self.project_and_deserialize(row),
         }
     }
 }
 
 #[async_trait]
-impl<T, P0, P1> ProjectNode<T> for (P0, P1)
-where
-    T: Table,
-    P0: project::ProjectFrom<Table = T> + Typed<T::DB> + project::ProjectAndProbe<T::DB>,
-    P1: project::ProjectFrom<Table = T> + Typed<T::DB> + project::ProjectAndProbe<T::DB>,
-{
-    type Output = (<P0::Ty as ty::Type>::Output, <P1::Ty as ty::Type>::Output);
-
-    async fn project_node(self, node: &Node<T>) -> UrmResult<Self::Output> {
-        match &node.phase {
-            Phase::Probe(probing) => {
-                self.0.project_and_probe(probing)?;
-                self.1.project_and_probe(probing)?;
-                never::never().await
+impl<S: Project> ProjectNode<S::Table> for project::Projection<S> {
+    type Output = S;
+
+    async fn project_node(self, node: &Node<S::Table>) -> UrmResult<S> {
+        match (S::project_fields(node).await?, &node.phase) {
+            (Some(value), _) => Ok(value),
+            (None, Phase::Probe(probing)) => probing.park().await,
+            (None, Phase::Deserialize(_)) => {
+                unreachable!("fields are always projected while deserializing")
             }
-            Phase::Deserialize => Err(UrmError::Deserialization),
         }
     }
 }
+
+/// Implement `ProjectNode` for a tuple of projections,
+/// probing all of them and deserializing into a tuple of their outputs.
+macro_rules! project_node_tuple {
+    ($($p:ident $idx:tt),+) => {
+        #[async_trait]
+        impl<T, $($p),+> ProjectNode<T> for ($($p,)+)
+        where
+            T: Table,
+            $(
+                $p: project::ProjectFrom<Table = T>
+                    + Typed<T::DB>
+                    + project::ProjectAndProbe<T::DB>
+                    + project::ProjectAndDeserialize<T::DB>,
+            )+
+        {
+            type Output = ($(<$p::Ty as ty::Type>::Output,)+);
+
+            async fn project_node(self, node: &Node<T>) -> UrmResult<Self::Output> {
+                match &node.phase {
+                    Phase::Probe(probing) => {
+                        $(self.$idx.project_and_probe(probing).await?;)+
+                        probing.park().await
+                    }
+                    Phase::Deserialize(row) => Ok(($(self.$idx.project_and_deserialize(row)?,)+)),
+                }
+            }
+        }
+    };
+}
+
+project_node_tuple!(P0 0, P1 1);
+project_node_tuple!(P0 0, P1 1, P2 2);
+project_node_tuple!(P0 0, P1 1, P2 2, P3 3);
+project_node_tuple!(P0 0, P1 1, P2 2, P3 3, P4 4);
+project_node_tuple!(P0 0, P1 1, P2 2, P3 3, P4 4, P5 5);
+project_node_tuple!(P0 0, P1 1, P2 2, P3 3, P4 4, P5 5, P6 6);
+project_node_tuple!(P0 0, P1 1, P2 2, P3 3, P4 4, P5 5, P6 6, P7 7);
+project_node_tuple!(P0 0, P1 1, P2 2, P3 3, P4 4, P5 5, P6 6, P7 7, P8 8);
+project_node_tuple!(P0 0, P1 1, P2 2, P3 3, P4 4, P5 5, P6 6, P7 7, P8 8, P9 9);
+project_node_tuple!(P0 0, P1 1, P2 2, P3 3, P4 4, P5 5, P6 6, P7 7, P8 8, P9 9, P10 10);
+project_node_tuple!(P0 0, P1 1, P2 2, P3 3, P4 4, P5 5, P6 6, P7 7, P8 8, P9 9, P10 10, P11 11);
+project_node_tuple!(
+    P0 0, P1 1, P2 2, P3 3, P4 4, P5 5, P6 6, P7 7, P8 8, P9 9, P10 10, P11 11, P12 12
+);
+project_node_tuple!(
+    P0 0, P1 1, P2 2, P3 3, P4 4, P5 5, P6 6, P7 7, P8 8, P9 9, P10 10, P11 11, P12 12, P13 13
+);
+project_node_tuple!(
+    P0 0, P1 1, P2 2, P3 3, P4 4, P5 5, P6 6, P7 7, P8 8, P9 9, P10 10, P11 11, P12 12, P13 13,
+    P14 14
+);
+project_node_tuple!(
+    P0 0, P1 1, P2 2, P3 3, P4 4, P5 5, P6 6, P7 7, P8 8, P9 9, P10 10, P11 11, P12 12, P13 13,
+    P14 14, P15 15
+);
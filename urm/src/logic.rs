@@ -11,6 +11,7 @@ enum LogicOp {
 /// Logical conjuction of two clauses.
 ///
 /// To use more than two clauses, just nest it: `And(x, And(y, z))`
+#[derive(Clone)]
 pub struct And<L, R>(pub L, pub R);
 
 impl<DB, L, R> Typed<DB> for And<L, R>
@@ -30,15 +31,12 @@ where
 {
     fn lower(self) -> Option<Lowered<DB>> {
         match (self.0.lower(), self.1.lower()) {
-            (Some(Lowered::And(lhs)), Some(Lowered::And(rhs))) => optimize(
-                LogicOp::And,
-                lhs.into_iter().chain(rhs.into_iter()).map(Option::Some),
-            ),
+            (Some(Lowered::And(lhs)), Some(Lowered::And(rhs))) => {
+                optimize(LogicOp::And, lhs.into_iter().chain(rhs).map(Option::Some))
+            }
             (Some(Lowered::And(lhs)), rhs) => optimize(
                 LogicOp::And,
-                lhs.into_iter()
-                    .map(Option::Some)
-                    .chain(Some(rhs).into_iter()),
+                lhs.into_iter().map(Option::Some).chain(Some(rhs)),
             ),
             (lhs, Some(Lowered::And(rhs))) => optimize(
                 LogicOp::And,
@@ -54,6 +52,7 @@ where
 /// Logical disjunction of two clauses.
 ///
 /// To use more than two clauses, just nest it: `And(x, And(y, z))`
+#[derive(Clone)]
 pub struct Or<L, R>(pub L, pub R);
 
 impl<DB, L, R> Typed<DB> for Or<L, R>
@@ -73,15 +72,12 @@ where
 {
     fn lower(self) -> Option<Lowered<DB>> {
         match (self.0.lower(), self.1.lower()) {
-            (Some(Lowered::Or(lhs)), Some(Lowered::Or(rhs))) => optimize(
-                LogicOp::Or,
-                lhs.into_iter().chain(rhs.into_iter()).map(Option::Some),
-            ),
+            (Some(Lowered::Or(lhs)), Some(Lowered::Or(rhs))) => {
+                optimize(LogicOp::Or, lhs.into_iter().chain(rhs).map(Option::Some))
+            }
             (Some(Lowered::Or(lhs)), rhs) => optimize(
                 LogicOp::Or,
-                lhs.into_iter()
-                    .map(Option::Some)
-                    .chain(Some(rhs).into_iter()),
+                lhs.into_iter().map(Option::Some).chain(Some(rhs)),
             ),
             (lhs, Some(Lowered::Or(rhs))) => optimize(
                 LogicOp::Or,
@@ -98,7 +94,7 @@ fn optimize<DB: Database>(
     op: LogicOp,
     clause_iter: impl Iterator<Item = Option<Lowered<DB>>>,
 ) -> Option<Lowered<DB>> {
-    let clauses: Vec<_> = clause_iter.filter_map(|clause| clause).collect();
+    let clauses: Vec<_> = clause_iter.flatten().collect();
     match clauses.len() {
         0 => None,
         1 => Some(clauses.into_iter().next().unwrap()),
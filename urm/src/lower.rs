@@ -32,14 +32,52 @@ where
     fn build(&self, builder: &mut QueryBuilder<DB>) {
         match self {
             Self::Expr(expr) => expr.build(builder),
+            // An empty conjunction is true, an empty disjunction is false
+            Self::And(clauses) if clauses.is_empty() => builder.push_bool(true),
+            Self::Or(clauses) if clauses.is_empty() => builder.push_bool(false),
             Self::And(clauses) => {
-                build_multiline_infix("AND", &clauses, builder);
+                build_multiline_infix("AND", clauses, builder);
             }
             Self::Or(clauses) => {
-                build_multiline_infix("OR", &clauses, builder);
+                build_multiline_infix("OR", clauses, builder);
             }
         }
     }
+
+    #[cfg(feature = "testing")]
+    fn as_eval(&self) -> Option<&dyn crate::testing::Eval<DB>> {
+        Some(self)
+    }
+}
+
+#[cfg(feature = "testing")]
+impl<DB: Database> crate::testing::Eval<DB> for Lowered<DB> {
+    /// Evaluates using SQL's three-valued logic, where `null` is unknown.
+    fn eval(&self, scope: &crate::testing::Scope) -> crate::UrmResult<serde_json::Value> {
+        use crate::testing::eval;
+
+        let (clauses, dominant) = match self {
+            Self::Expr(expr) => return eval(expr.as_ref(), scope),
+            Self::And(clauses) => (clauses, false),
+            Self::Or(clauses) => (clauses, true),
+        };
+
+        let mut unknown = false;
+        for clause in clauses {
+            match eval(clause, scope)? {
+                serde_json::Value::Bool(value) if value == dominant => {
+                    return Ok(serde_json::Value::Bool(dominant))
+                }
+                serde_json::Value::Null => unknown = true,
+                _ => {}
+            }
+        }
+
+        Ok(match unknown {
+            true => serde_json::Value::Null,
+            false => serde_json::Value::Bool(!dominant),
+        })
+    }
 }
 
 fn build_multiline_infix<DB: Database>(
@@ -47,20 +85,16 @@ fn build_multiline_infix<DB: Database>(
     clauses: &[Lowered<DB>],
     builder: &mut QueryBuilder<DB>,
 ) {
-    if clauses.is_empty() {
-        return;
-    }
-
     builder.push("(");
     builder.newline_indent();
 
-    let mut iterator = clauses.into_iter();
+    let mut iterator = clauses.iter();
     let mut item = iterator.next();
 
     while let Some(cur) = item {
         cur.build(builder);
         let next_item = iterator.next();
-        if let Some(_) = next_item.as_ref() {
+        if next_item.is_some() {
             builder.newline();
             builder.push(infix);
             builder.newline();
@@ -110,17 +144,35 @@ where
 }
 
 pub trait BuildRange<DB: Database>: std::fmt::Debug + Send + Sync + 'static {
-    fn build_range(&self, builder: &mut QueryBuilder<DB>);
+    /// The number of rows to select, if limited, and the number of rows to skip.
+    fn limit_offset(&self) -> (Option<usize>, usize);
+
+    fn build_range(&self, builder: &mut QueryBuilder<DB>) {
+        let (limit, offset) = self.limit_offset();
+        builder.push_limit_offset(limit, offset);
+    }
 }
 
 impl<DB: Database> BuildRange<DB> for () {
-    fn build_range(&self, _builder: &mut QueryBuilder<DB>) {}
+    fn limit_offset(&self) -> (Option<usize>, usize) {
+        (None, 0)
+    }
 }
 
+/// Selects the rows `start..end`, i.e. `end` is exclusive.
 impl<DB: Database> BuildRange<DB> for ::std::ops::Range<usize> {
-    fn build_range(&self, _builder: &mut QueryBuilder<DB>) {}
+    fn limit_offset(&self) -> (Option<usize>, usize) {
+        (Some(self.end.saturating_sub(self.start)), self.start)
+    }
 }
 
+/// Selects the rows `start..end`, where a missing `start` is the first row
+/// and a missing `end` is past the last row.
 impl<DB: Database> BuildRange<DB> for ::std::ops::Range<Option<usize>> {
-    fn build_range(&self, _builder: &mut QueryBuilder<DB>) {}
+    fn limit_offset(&self) -> (Option<usize>, usize) {
+        let offset = self.start.unwrap_or(0);
+        let limit = self.end.map(|end| end.saturating_sub(offset));
+
+        (limit, offset)
+    }
 }
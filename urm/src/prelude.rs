@@ -2,3 +2,4 @@ pub use crate::filter::Filter;
 pub use crate::filter::Range;
 pub use crate::foreign::ProjectForeign;
 pub use crate::project::ProjectFrom;
+pub use crate::Project;
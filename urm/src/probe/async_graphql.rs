@@ -0,0 +1,167 @@
+//!
+//! Probing driven by async-graphql.
+//!
+
+use ::async_graphql::context::Context;
+use ::async_graphql::parser::types::{Field, Selection};
+use ::async_graphql::Positioned;
+use async_trait::async_trait;
+use std::any::Any;
+
+use super::{probe_field, ProbeDriver, ProbeFailure};
+use crate::database::Database;
+use crate::engine::Probing;
+
+///
+/// A `ProbeDriver` walking the selection set of an async-graphql field,
+/// resolving each selected field through `ContainerType::resolve_field`.
+///
+pub struct ContextDriver<'c> {
+    ctx: &'c Context<'c>,
+}
+
+impl<'c> ContextDriver<'c> {
+    pub fn new(ctx: &'c Context<'c>) -> Self {
+        Self { ctx }
+    }
+}
+
+#[async_trait]
+impl<'c, C> ProbeDriver<C> for ContextDriver<'c>
+where
+    C: ::async_graphql::ContainerType,
+{
+    fn alias(&self) -> Option<&str> {
+        self.ctx
+            .item
+            .node
+            .alias
+            .as_ref()
+            .map(|alias| alias.node.as_str())
+    }
+
+    fn data<T: Any + Send + Sync>(&self) -> Option<&T> {
+        self.ctx.data_opt::<T>()
+    }
+
+    async fn probe<DB: Database>(&self, container: &C, probing: &Probing<DB>) -> Vec<ProbeFailure> {
+        let ctx_obj = self
+            .ctx
+            .with_selection_set(&self.ctx.item.node.selection_set);
+        let mut fields = vec![];
+        let mut failures = vec![];
+        collect_fields::<C>(&ctx_obj, &mut fields, &mut failures);
+
+        for field in fields {
+            let ctx_field = ctx_obj.with_field(field);
+            let future = async {
+                container
+                    .resolve_field(&ctx_field)
+                    .await
+                    .map(|_| ())
+                    .map_err(|error| error.message)
+            };
+
+            failures.extend(probe_field(probing, path_of(&ctx_field), future).await);
+        }
+
+        failures
+    }
+}
+
+/// Collect the fields of a selection set to be probed, expanding fragments.
+fn collect_fields<'a, T: ::async_graphql::ContainerType>(
+    ctx: &::async_graphql::ContextSelectionSet<'a>,
+    fields: &mut Vec<&'a Positioned<Field>>,
+    failures: &mut Vec<ProbeFailure>,
+) {
+    let query_env: &'a ::async_graphql::context::QueryEnv = ctx.query_env;
+
+    for selection in &ctx.item.node.items {
+        if ctx.is_skip(selection.node.directives()).unwrap_or(true) {
+            continue;
+        }
+
+        let field = match &selection.node {
+            Selection::Field(field) => field,
+            Selection::FragmentSpread(spread) => {
+                let fragment_name = &spread.node.fragment_name.node;
+                match query_env.fragments.get(fragment_name) {
+                    Some(fragment) => {
+                        if applies_to::<T>(ctx, Some(&fragment.node.type_condition.node.on.node)) {
+                            collect_fields::<T>(
+                                &ctx.with_selection_set(&fragment.node.selection_set),
+                                fields,
+                                failures,
+                            );
+                        }
+                    }
+                    None => failures.push(ProbeFailure {
+                        path: path_of(ctx),
+                        message: format!(r#"Unknown fragment "{}"."#, fragment_name),
+                    }),
+                }
+                continue;
+            }
+            Selection::InlineFragment(fragment) => {
+                let type_condition = fragment
+                    .node
+                    .type_condition
+                    .as_ref()
+                    .map(|condition| &condition.node.on.node);
+
+                if applies_to::<T>(ctx, type_condition) {
+                    collect_fields::<T>(
+                        &ctx.with_selection_set(&fragment.node.selection_set),
+                        fields,
+                        failures,
+                    );
+                }
+                continue;
+            }
+        };
+
+        if field.node.name.node == "__typename" {
+            continue;
+        }
+
+        if ctx.is_ifdef(&field.node.directives) {
+            if let Some(::async_graphql::registry::MetaType::Object { fields, .. }) =
+                ctx.schema_env.registry.types.get(T::type_name().as_ref())
+            {
+                if !fields.contains_key(field.node.name.node.as_str()) {
+                    continue;
+                }
+            }
+        }
+
+        fields.push(field);
+    }
+}
+
+/// Whether a fragment with the given type condition applies to the container type `T`,
+/// either by naming it or by naming an interface it implements.
+fn applies_to<T: ::async_graphql::ContainerType>(
+    ctx: &::async_graphql::ContextSelectionSet,
+    type_condition: Option<&::async_graphql::Name>,
+) -> bool {
+    let type_name = T::type_name();
+
+    match type_condition {
+        None => true,
+        Some(condition) => {
+            condition.as_str() == type_name
+                || matches!(
+                    ctx.schema_env.registry.implements.get(type_name.as_ref()),
+                    Some(interfaces) if interfaces.contains(condition.as_str())
+                )
+        }
+    }
+}
+
+fn path_of<Item>(ctx: &::async_graphql::context::ContextBase<'_, Item>) -> String {
+    ctx.path_node
+        .as_ref()
+        .map(ToString::to_string)
+        .unwrap_or_default()
+}
@@ -0,0 +1,388 @@
+//!
+//! Probing driven by juniper.
+//!
+//! juniper resolves fields synchronously, so a query is executed in two passes by `execute`.
+//! The first pass probes: each root `Select::probe_with_executor` walks the fields selected on
+//! its container by running their juniper resolvers, which reach `project` and contribute to
+//! the query of the select. The queries are then executed, and the second pass deserializes:
+//! the same resolvers run again, reading the fetched rows.
+//!
+//! Resolvers therefore run twice, which also holds for fields not backed by a select,
+//! so `execute` is meant for queries, not for mutations.
+//!
+
+use ::juniper::meta::MetaType;
+use ::juniper::{
+    Arguments, ExecutionError, ExecutionResult, Executor, GraphQLError, GraphQLType,
+    LookAheadMethods, Object, Registry, RootNode, ScalarRefValue, ScalarValue, Value, Variables,
+};
+use async_trait::async_trait;
+use futures_util::FutureExt;
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+
+use super::{Data, ProbeDriver, ProbeFailure};
+use crate::database::Database;
+use crate::engine::Probing;
+use crate::{Probe, ProjectNode, Table, UrmError, UrmResult};
+
+type Rows = UrmResult<Vec<serde_json::Value>>;
+
+type Fetch = Pin<Box<dyn Future<Output = Rows> + Send>>;
+
+/// The pass of `execute` being run on this thread.
+enum Pass {
+    /// Root selects register the fetch of their probed query, by response key.
+    Probe(Vec<(String, Fetch)>),
+
+    /// Root selects take the rows they fetched, by response key.
+    Deserialize(HashMap<String, Rows>),
+}
+
+struct Session {
+    pass: RefCell<Pass>,
+
+    /// The response keys of the fields being probed, from the root field.
+    path: RefCell<Vec<String>>,
+}
+
+thread_local! {
+    static SESSION: RefCell<Option<Rc<Session>>> = const { RefCell::new(None) };
+}
+
+fn current_session() -> Option<Rc<Session>> {
+    SESSION.with(|session| session.borrow().clone())
+}
+
+/// Run `func` with `session` as the current session of this thread.
+fn with_session<R>(session: Rc<Session>, func: impl FnOnce() -> R) -> R {
+    struct Restore(Option<Rc<Session>>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            SESSION.with(|session| *session.borrow_mut() = previous);
+        }
+    }
+
+    let _restore = Restore(SESSION.with(|current| current.replace(Some(session))));
+    func()
+}
+
+/// Run `func` with the field keyed `key` appended to the probed path,
+/// passing it the path of that field, e.g. `publications.editions`.
+fn enter<R>(key: &str, func: impl FnOnce(String) -> R) -> R {
+    struct Leave(Option<Rc<Session>>);
+
+    impl Drop for Leave {
+        fn drop(&mut self) {
+            if let Some(session) = &self.0 {
+                session.path.borrow_mut().pop();
+            }
+        }
+    }
+
+    let session = current_session();
+    let path = match &session {
+        Some(session) => {
+            let mut path = session.path.borrow_mut();
+            path.push(key.to_string());
+            path.join(".")
+        }
+        None => key.to_string(),
+    };
+
+    let _leave = Leave(session);
+    func(path)
+}
+
+///
+/// Execute a juniper query whose root fields are resolved by `Select::probe_with_executor`.
+///
+/// The query is executed twice by `juniper::execute`, first to probe, then to deserialize,
+/// and the queries of the root selects are executed in between. The response and field
+/// errors are those of the second pass.
+///
+pub async fn execute<'a, S, CtxT, QueryT, MutationT>(
+    document_source: &'a str,
+    operation_name: Option<&str>,
+    root_node: &'a RootNode<'a, QueryT, MutationT, S>,
+    variables: &Variables<S>,
+    context: &CtxT,
+) -> Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError<'a>>
+where
+    S: ScalarValue,
+    for<'b> &'b S: ScalarRefValue<'b>,
+    QueryT: GraphQLType<S, Context = CtxT>,
+    MutationT: GraphQLType<S, Context = CtxT>,
+{
+    let fetches = {
+        let session = Rc::new(Session {
+            pass: RefCell::new(Pass::Probe(vec![])),
+            path: RefCell::new(vec![]),
+        });
+
+        with_session(session.clone(), || {
+            ::juniper::execute(
+                document_source,
+                operation_name,
+                root_node,
+                variables,
+                context,
+            )
+        })?;
+
+        match session.pass.replace(Pass::Probe(vec![])) {
+            Pass::Probe(fetches) => fetches,
+            Pass::Deserialize(_) => unreachable!(),
+        }
+    };
+
+    let rows = futures_util::future::join_all(
+        fetches
+            .into_iter()
+            .map(|(key, fetch)| fetch.map(move |rows| (key, rows))),
+    )
+    .await;
+
+    let session = Rc::new(Session {
+        pass: RefCell::new(Pass::Deserialize(rows.into_iter().collect())),
+        path: RefCell::new(vec![]),
+    });
+
+    with_session(session, || {
+        ::juniper::execute(
+            document_source,
+            operation_name,
+            root_node,
+            variables,
+            context,
+        )
+    })
+}
+
+/// What a root select does in the current pass of `execute`.
+pub(crate) enum RootPass {
+    Probe,
+    Deserialize(Rows),
+}
+
+/// The pass of the root select keyed `key`, failing outside of `execute`.
+pub(crate) fn root_pass(key: &str) -> UrmResult<RootPass> {
+    let failure = |message: &str| {
+        UrmError::Probe(vec![ProbeFailure {
+            path: key.to_string(),
+            message: message.to_string(),
+        }])
+    };
+
+    let session = current_session()
+        .ok_or_else(|| failure("not executed through `urm::probe::juniper::execute`"))?;
+    let pass = session.pass.borrow();
+
+    match &*pass {
+        Pass::Probe(_) => Ok(RootPass::Probe),
+        Pass::Deserialize(rows) => rows
+            .get(key)
+            .cloned()
+            .map(RootPass::Deserialize)
+            .ok_or_else(|| failure("was not probed")),
+    }
+}
+
+/// Register the fetch of the root select keyed `key`, awaited between the passes of `execute`.
+pub(crate) fn fetch_later<F>(key: String, fetch: F)
+where
+    F: Future<Output = Rows> + Send + 'static,
+{
+    if let Some(session) = current_session() {
+        if let Pass::Probe(fetches) = &mut *session.pass.borrow_mut() {
+            fetches.push((key, Box::pin(fetch)));
+        }
+    }
+}
+
+/// Probe the fields selected on the container of a root select, keyed `key`.
+pub(crate) fn probe_root<C, CtxT, S, DB>(
+    key: &str,
+    executor: &Executor<CtxT, S>,
+    container: &C,
+    probing: &Probing<DB>,
+) -> Vec<ProbeFailure>
+where
+    C: GraphQLType<S, Context = CtxT, TypeInfo = ()>,
+    S: ScalarValue,
+    for<'b> &'b S: ScalarRefValue<'b>,
+    DB: Database,
+{
+    enter(key, |_| walk(executor, container, probing))
+}
+
+/// The key of the field being resolved in the response: its alias, or else its name.
+pub(crate) fn response_key<CtxT, S>(executor: &Executor<CtxT, S>) -> String
+where
+    S: ScalarValue,
+    for<'b> &'b S: ScalarRefValue<'b>,
+{
+    executor.look_ahead().field_name().to_string()
+}
+
+/// Resolve the fields selected on `container` through `Probed`, returning the fields that failed.
+fn walk<C, CtxT, S, DB>(
+    executor: &Executor<CtxT, S>,
+    container: &C,
+    probing: &Probing<DB>,
+) -> Vec<ProbeFailure>
+where
+    C: GraphQLType<S, Context = CtxT, TypeInfo = ()>,
+    S: ScalarValue,
+    for<'b> &'b S: ScalarRefValue<'b>,
+    DB: Database,
+{
+    let probed = Probed {
+        container,
+        probing,
+        failures: RefCell::new(vec![]),
+    };
+
+    // The resolved value is a placeholder, only the failures matter
+    let _ = executor.resolve(&(), &probed);
+
+    probed.failures.into_inner()
+}
+
+///
+/// A container being probed, resolving each selected field with the container's
+/// own juniper resolver, which is expected to park at `project`.
+///
+/// Every field resolves to a placeholder, so that juniper doesn't skip the
+/// remaining fields after a failure, which is recorded instead.
+///
+struct Probed<'p, C, DB: Database> {
+    container: &'p C,
+    probing: &'p Probing<DB>,
+    failures: RefCell<Vec<ProbeFailure>>,
+}
+
+impl<'p, C, DB, S> GraphQLType<S> for Probed<'p, C, DB>
+where
+    C: GraphQLType<S, TypeInfo = ()>,
+    DB: Database,
+    S: ScalarValue,
+    for<'b> &'b S: ScalarRefValue<'b>,
+{
+    type Context = C::Context;
+    type TypeInfo = ();
+
+    fn name(info: &()) -> Option<&str> {
+        C::name(info)
+    }
+
+    fn meta<'r>(info: &(), registry: &mut Registry<'r, S>) -> MetaType<'r, S>
+    where
+        S: 'r,
+    {
+        C::meta(info, registry)
+    }
+
+    fn resolve_field(
+        &self,
+        info: &(),
+        field_name: &str,
+        arguments: &Arguments<S>,
+        executor: &Executor<C::Context, S>,
+    ) -> ExecutionResult<S> {
+        let key = response_key(executor);
+
+        enter(&key, |path| {
+            let parked = self.probing.parked();
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                self.container
+                    .resolve_field(info, field_name, arguments, executor)
+            }));
+
+            let message = match result {
+                Ok(Err(_)) if self.probing.parked() > parked => None,
+                Ok(Err(error)) => Some(error.message().to_string()),
+                Ok(Ok(_)) => Some("completed without reaching `urm::project`".to_string()),
+                Err(panic) => Some(format!("panicked: {}", super::panic_message(&panic))),
+            };
+
+            if let Some(message) = message {
+                self.failures
+                    .borrow_mut()
+                    .push(ProbeFailure { path, message });
+            }
+        });
+
+        Ok(Value::object(Object::with_capacity(0)))
+    }
+
+    fn concrete_type_name(&self, _: &Self::Context, info: &()) -> String {
+        C::name(info).unwrap_or_default().to_string()
+    }
+}
+
+///
+/// Probes the container selected by the juniper field being resolved,
+/// for foreign projections through `ProjectForeign::probe_with_executor`.
+///
+/// juniper's executor only exposes the response key of the field being resolved,
+/// so the projection is keyed by it, whether it is an alias or the field name.
+///
+pub struct ExecutorDriver<'e, 'a, CtxT, S> {
+    executor: &'e Executor<'a, CtxT, S>,
+    response_key: String,
+}
+
+impl<'e, 'a, CtxT, S> ExecutorDriver<'e, 'a, CtxT, S>
+where
+    S: ScalarValue,
+    for<'b> &'b S: ScalarRefValue<'b>,
+{
+    pub fn new(executor: &'e Executor<'a, CtxT, S>) -> Self {
+        Self {
+            executor,
+            response_key: response_key(executor),
+        }
+    }
+}
+
+#[async_trait]
+impl<'e, 'a, C, CtxT, S> ProbeDriver<C> for ExecutorDriver<'e, 'a, CtxT, S>
+where
+    C: GraphQLType<S, Context = CtxT, TypeInfo = ()> + Sync,
+    CtxT: AsRef<Data> + Sync,
+    S: ScalarValue + Send + Sync,
+    for<'b> &'b S: ScalarRefValue<'b>,
+{
+    fn alias(&self) -> Option<&str> {
+        Some(&self.response_key)
+    }
+
+    fn data<T: Any + Send + Sync>(&self) -> Option<&T> {
+        self.executor.context().as_ref().get::<T>()
+    }
+
+    async fn probe<DB: Database>(&self, container: &C, probing: &Probing<DB>) -> Vec<ProbeFailure> {
+        walk(self.executor, container, probing)
+    }
+}
+
+/// `urm::project` for synchronous juniper resolvers.
+///
+/// While probing, projecting parks the node, which is reported as `UrmError::NotReady`.
+pub fn project<T, P, M>(probe: &P, arg: M) -> UrmResult<M::Output>
+where
+    T: Table,
+    P: Probe<Table = T>,
+    M: ProjectNode<T>,
+{
+    crate::project(probe, arg)
+        .now_or_never()
+        .unwrap_or(Err(UrmError::NotReady))
+}
@@ -0,0 +1,272 @@
+//!
+//! Probing: walking the fields selected on a probe container, so that each
+//! resolver reaches `urm::project` and contributes to the query being built.
+//!
+//! Probing is framework-neutral. A `ProbeDriver` walks the fields selected on one
+//! container, and the async-graphql integration is one such driver. Without a
+//! GraphQL framework, a `SelectionDriver` walks a plain `Selection` tree.
+//! juniper fields are probed by running their juniper resolvers, see `juniper::execute`.
+//!
+
+use async_trait::async_trait;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::Poll;
+use std::time::Duration;
+
+use crate::database::Database;
+use crate::engine::Probing;
+use crate::UrmResult;
+
+#[cfg(feature = "async_graphql")]
+pub mod async_graphql;
+
+#[cfg(feature = "juniper")]
+pub mod juniper;
+
+///
+/// A GraphQL field that failed while being probed,
+/// either by returning an error or by panicking.
+///
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct ProbeFailure {
+    /// The path of the field within the GraphQL query, e.g. `editions.publication.title`.
+    pub path: String,
+
+    pub message: String,
+}
+
+impl std::fmt::Display for ProbeFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "`{}`: {}", self.path, self.message)
+    }
+}
+
+///
+/// Drives the probing of a container `C`, by walking the fields selected on it.
+///
+/// A driver represents the field that selected the container,
+/// and is handed to nested foreign probes through `probe_with_driver`.
+///
+#[async_trait]
+pub trait ProbeDriver<C: Sync>: Send + Sync {
+    /// The alias of the field that selected the container, keying its foreign projection.
+    fn alias(&self) -> Option<&str>;
+
+    /// Look up data shared by all resolvers, like a `database::Connection` or an `inspect::Inspector`.
+    fn data<T: Any + Send + Sync>(&self) -> Option<&T>;
+
+    /// Resolve every field selected on the container, each through `probe_field`,
+    /// returning the fields that failed.
+    async fn probe<DB: Database>(&self, container: &C, probing: &Probing<DB>) -> Vec<ProbeFailure>;
+}
+
+/// How long a resolver may take to reach `urm::project` while probing, before it is given up on.
+const PROBE_DEADLINE: Duration = Duration::from_secs(1);
+
+/// Drive one resolver future until it reaches `urm::project` on the select being probed,
+/// which parks it for the rest of probing.
+///
+/// Resolvers may await other futures before projecting, like a timer or a channel.
+/// The resolver is polled again when one of those wakes it, until `PROBE_DEADLINE` passes.
+/// A resolver awaiting a future that is never woken, like `urm::project` on another select,
+/// is given up on at the deadline.
+///
+/// A failure at `path` is returned when the resolver errors, panics, completes without
+/// projecting, or doesn't project before the deadline.
+pub async fn probe_field<DB, F>(
+    probing: &Probing<DB>,
+    path: String,
+    future: F,
+) -> Option<ProbeFailure>
+where
+    DB: Database,
+    F: Future<Output = Result<(), String>>,
+{
+    futures_util::pin_mut!(future);
+    let parked = probing.parked();
+    let mut deadline = futures_timer::Delay::new(PROBE_DEADLINE);
+
+    let outcome = futures_util::future::poll_fn(|cx| {
+        let poll =
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| future.as_mut().poll(cx)));
+
+        match poll {
+            Ok(Poll::Ready(Ok(()))) => {
+                Poll::Ready(Err("completed without reaching `urm::project`".to_string()))
+            }
+            Ok(Poll::Ready(Err(message))) => Poll::Ready(Err(message)),
+            Ok(Poll::Pending) if probing.parked() > parked => Poll::Ready(Ok(())),
+            Ok(Poll::Pending) => match Pin::new(&mut deadline).poll(cx) {
+                Poll::Ready(()) => Poll::Ready(Err(format!(
+                    "did not reach `urm::project` within {:?}",
+                    PROBE_DEADLINE
+                ))),
+                Poll::Pending => Poll::Pending,
+            },
+            Err(panic) => Poll::Ready(Err(format!("panicked: {}", panic_message(&panic)))),
+        }
+    })
+    .await;
+
+    outcome.err().map(|message| ProbeFailure { path, message })
+}
+
+fn panic_message(panic: &Box<dyn Any + Send>) -> &str {
+    if let Some(message) = panic.downcast_ref::<&'static str>() {
+        message
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown panic payload"
+    }
+}
+
+///
+/// A field of a framework-neutral selection tree.
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Selection {
+    pub name: String,
+    pub alias: Option<String>,
+    pub arguments: serde_json::Map<String, serde_json::Value>,
+
+    /// The fields selected on the value of this field.
+    pub children: Vec<Selection>,
+}
+
+impl Selection {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_alias(mut self, alias: impl Into<String>) -> Self {
+        self.alias = Some(alias.into());
+        self
+    }
+
+    pub fn with_argument(mut self, name: impl Into<String>, value: serde_json::Value) -> Self {
+        self.arguments.insert(name.into(), value);
+        self
+    }
+
+    pub fn with_child(mut self, child: Selection) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// The key of this field in a response: its alias, or else its name.
+    pub fn response_key(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.name)
+    }
+}
+
+///
+/// Data shared by the resolvers of a `SelectionDriver` or a juniper query, looked up by type.
+///
+#[derive(Default)]
+pub struct Data {
+    values: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
+
+impl Data {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with<T: Any + Send + Sync>(mut self, value: T) -> Self {
+        self.values.insert(TypeId::of::<T>(), Box::new(value));
+        self
+    }
+
+    pub fn get<T: Any + Send + Sync>(&self) -> Option<&T> {
+        self.values
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref())
+    }
+}
+
+///
+/// A container probed by walking a `Selection` tree, without a GraphQL framework.
+///
+#[async_trait]
+pub trait ResolveSelection: Send + Sync {
+    /// Resolve one field selected on this container while probing,
+    /// typically by awaiting `urm::project`.
+    async fn resolve_selection(&self, field: &SelectionDriver<'_>) -> UrmResult<()>;
+}
+
+///
+/// A `ProbeDriver` walking a `Selection` tree.
+///
+#[derive(Clone, Copy)]
+pub struct SelectionDriver<'a> {
+    selection: &'a Selection,
+    parent: Option<&'a SelectionDriver<'a>>,
+    data: &'a Data,
+}
+
+impl<'a> SelectionDriver<'a> {
+    /// A driver for the root field `selection`, whose children are selected on the root container.
+    pub fn new(selection: &'a Selection, data: &'a Data) -> Self {
+        Self {
+            selection,
+            parent: None,
+            data,
+        }
+    }
+
+    pub fn selection(&self) -> &'a Selection {
+        self.selection
+    }
+
+    /// The response keys leading to this field, separated by dots.
+    pub fn path(&self) -> String {
+        match self.parent {
+            Some(parent) => format!("{}.{}", parent.path(), self.selection.response_key()),
+            None => self.selection.response_key().to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl<'a, C> ProbeDriver<C> for SelectionDriver<'a>
+where
+    C: ResolveSelection,
+{
+    fn alias(&self) -> Option<&str> {
+        self.selection.alias.as_deref()
+    }
+
+    fn data<T: Any + Send + Sync>(&self) -> Option<&T> {
+        self.data.get()
+    }
+
+    async fn probe<DB: Database>(&self, container: &C, probing: &Probing<DB>) -> Vec<ProbeFailure> {
+        let mut failures = vec![];
+
+        for child in &self.selection.children {
+            let field = SelectionDriver {
+                selection: child,
+                parent: Some(self),
+                data: self.data,
+            };
+
+            let future = async {
+                container
+                    .resolve_selection(&field)
+                    .await
+                    .map_err(|error| error.to_string())
+            };
+
+            failures.extend(probe_field(probing, field.path(), future).await);
+        }
+
+        failures
+    }
+}
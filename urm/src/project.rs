@@ -4,11 +4,63 @@
 
 use crate::database::Database;
 use crate::engine::Probing;
+use crate::ty::{Type, Typed};
 use crate::{Table, UrmResult};
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
 pub struct LocalId(pub u16);
 
+impl LocalId {
+    /// The key of this projection within a JSON row.
+    pub fn key(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+///
+/// The key of a projection within a select.
+///
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum ProjectionKey {
+    /// A field declared in `#[urm::table]`.
+    ///
+    /// The same foreign field may be projected several times under different GraphQL
+    /// aliases, each with its own arguments, so an aliased projection is keyed by
+    /// its alias in addition to its `LocalId`.
+    Field {
+        local_id: LocalId,
+        alias: Option<String>,
+    },
+    /// An expression projected with `column::expression`, keyed by a hash of its SQL.
+    Expression(u64),
+}
+
+impl ProjectionKey {
+    /// The key of this projection within a JSON row, e.g. `5`, `5:alias` or `x1f0e5c2a9d3b7a10`.
+    pub fn key(&self) -> String {
+        match self {
+            Self::Field {
+                local_id,
+                alias: Some(alias),
+            } => format!("{}:{}", local_id.0, alias),
+            Self::Field {
+                local_id,
+                alias: None,
+            } => local_id.key(),
+            Self::Expression(hash) => format!("x{:016x}", hash),
+        }
+    }
+}
+
+impl From<LocalId> for ProjectionKey {
+    fn from(local_id: LocalId) -> Self {
+        Self::Field {
+            local_id,
+            alias: None,
+        }
+    }
+}
+
 /// # ProjectFrom
 ///
 /// Types starting out as projection builders implement this trait.
@@ -28,6 +80,45 @@ pub trait ProjectFrom: Sized + Send + Sync {
 ///
 /// Not all `ProjectFrom` types implement `ProjectAndProbe`, and
 /// may need further mapping before reaching this typestate.
+#[async_trait::async_trait]
 pub trait ProjectAndProbe<DB: Database> {
-    fn project_and_probe(self, probing: &Probing<DB>) -> UrmResult<()>;
+    async fn project_and_probe(self, probing: &Probing<DB>) -> UrmResult<()>;
+}
+
+/// ProjectAndDeserialize is the counterpart of `ProjectAndProbe`,
+/// reading a projected value back out of a row returned from the database.
+///
+/// A row is a JSON object keyed by each projection's `ProjectionKey`.
+pub trait ProjectAndDeserialize<DB: Database>: Typed<DB> {
+    fn project_and_deserialize(
+        self,
+        row: &serde_json::Value,
+    ) -> UrmResult<<Self::Ty as Type>::Output>;
+}
+
+///
+/// The projection of a `Project` struct `S`, see `Project::projection`.
+///
+pub struct Projection<S> {
+    output: std::marker::PhantomData<S>,
+}
+
+impl<S> Projection<S> {
+    pub fn new() -> Self {
+        Self {
+            output: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<S> Default for Projection<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> Clone for Projection<S> {
+    fn clone(&self) -> Self {
+        Self::new()
+    }
 }
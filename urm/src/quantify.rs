@@ -5,9 +5,26 @@
 //! into either Self or some collection of Self.
 //!
 
+/// The runtime counterpart of a quantification,
+/// i.e. how many values a query should produce.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Quantity {
+    /// At most one value
+    One,
+    /// Any number of values
+    Many,
+}
+
 /// Quantify some type.
 pub trait Quantify<U> {
     type Output;
+
+    const QUANTITY: Quantity;
+
+    /// Quantify a sequence of units.
+    /// Returns the number of units when it does not fit the quantification.
+    fn quantify(units: Vec<U>) -> Result<Self::Output, usize>;
 }
 
 /// Quantify a type as itself, i.e. no quantification.
@@ -15,6 +32,15 @@ pub struct AsSelf;
 
 impl<U> Quantify<U> for AsSelf {
     type Output = U;
+
+    const QUANTITY: Quantity = Quantity::One;
+
+    fn quantify(units: Vec<U>) -> Result<Self::Output, usize> {
+        match units.len() {
+            1 => Ok(units.into_iter().next().unwrap()),
+            rows => Err(rows),
+        }
+    }
 }
 
 /// Quantify a type using `Option<_>`.
@@ -22,6 +48,15 @@ pub struct AsOption;
 
 impl<U> Quantify<U> for AsOption {
     type Output = Option<U>;
+
+    const QUANTITY: Quantity = Quantity::One;
+
+    fn quantify(units: Vec<U>) -> Result<Self::Output, usize> {
+        match units.len() {
+            0 | 1 => Ok(units.into_iter().next()),
+            rows => Err(rows),
+        }
+    }
 }
 
 /// Quantify a type using a `Vec<_>`.
@@ -29,4 +64,10 @@ pub struct AsVec;
 
 impl<U> Quantify<U> for AsVec {
     type Output = Vec<U>;
+
+    const QUANTITY: Quantity = Quantity::Many;
+
+    fn quantify(units: Vec<U>) -> Result<Self::Output, usize> {
+        Ok(units)
+    }
 }
@@ -0,0 +1,434 @@
+//!
+//! Dates and times, available with the `chrono` and `time` features.
+//!
+//! Rows travel as JSON, where databases write dates and times as text in
+//! slightly different formats, which each type parses using its own crate, see `decode`.
+//!
+
+use std::any::TypeId;
+
+/// Rust types of dates and times, which the temporal functions apply to.
+pub trait Temporal:
+    serde::Serialize + serde::de::DeserializeOwned + Send + Sync + Sized + 'static
+{
+    /// Whether values have a time of day, not only a date.
+    const TIME_OF_DAY: bool;
+
+    /// Whether values are points in time having an offset from UTC.
+    const OFFSET: bool;
+
+    /// The current date and time, in UTC.
+    fn now() -> Self;
+
+    /// Parse the text of a value as written by any of the databases,
+    /// where the time of day may be separated by a space instead of a `T`.
+    fn parse(text: &str) -> Option<Self>;
+
+    /// The start of the year, month, day, hour, minute or second.
+    #[cfg(feature = "testing")]
+    fn truncate(&self, field: DateField) -> Option<Self>;
+
+    #[cfg(feature = "testing")]
+    fn extract(&self, field: DateField) -> i64;
+
+    /// Add an interval like Postgres does: first the months, keeping the day
+    /// within the resulting month, then the days and the seconds.
+    #[cfg(feature = "testing")]
+    fn add_interval(&self, interval: Interval) -> Option<Self>;
+}
+
+/// A field of a date and time, which `DateTrunc` truncates to and `Extract` extracts.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DateField {
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+impl DateField {
+    /// The name of the field in Postgres and MySQL.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Year => "YEAR",
+            Self::Month => "MONTH",
+            Self::Day => "DAY",
+            Self::Hour => "HOUR",
+            Self::Minute => "MINUTE",
+            Self::Second => "SECOND",
+        }
+    }
+}
+
+///
+/// A length of time in months, days and seconds, which are kept apart
+/// because neither months nor days (across daylight saving changes) have a fixed length.
+///
+/// `Interval::days(1) + Interval::hours(12)`
+///
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Interval {
+    pub months: i32,
+    pub days: i32,
+    pub seconds: i64,
+}
+
+impl Interval {
+    pub fn years(years: i32) -> Self {
+        Self::months(years * 12)
+    }
+
+    pub fn months(months: i32) -> Self {
+        Self {
+            months,
+            ..Self::default()
+        }
+    }
+
+    pub fn weeks(weeks: i32) -> Self {
+        Self::days(weeks * 7)
+    }
+
+    pub fn days(days: i32) -> Self {
+        Self {
+            days,
+            ..Self::default()
+        }
+    }
+
+    pub fn hours(hours: i64) -> Self {
+        Self::seconds(hours * 3600)
+    }
+
+    pub fn minutes(minutes: i64) -> Self {
+        Self::seconds(minutes * 60)
+    }
+
+    pub fn seconds(seconds: i64) -> Self {
+        Self {
+            seconds,
+            ..Self::default()
+        }
+    }
+}
+
+impl std::ops::Add for Interval {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            months: self.months + rhs.months,
+            days: self.days + rhs.days,
+            seconds: self.seconds + rhs.seconds,
+        }
+    }
+}
+
+impl std::ops::Neg for Interval {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            months: -self.months,
+            days: -self.days,
+            seconds: -self.seconds,
+        }
+    }
+}
+
+///
+/// The JSON of a date or time of the type `U` written by a database as text,
+/// in the representation `U` deserializes from, if `U` is a `Temporal` type.
+///
+pub(crate) fn decode<U: 'static>(value: &serde_json::Value) -> Option<serde_json::Value> {
+    let text = value.as_str()?;
+    let decoders = [
+        #[cfg(feature = "chrono")]
+        decoder::<chrono::NaiveDate>(),
+        #[cfg(feature = "chrono")]
+        decoder::<chrono::NaiveDateTime>(),
+        #[cfg(feature = "chrono")]
+        decoder::<chrono::DateTime<chrono::Utc>>(),
+        #[cfg(feature = "time")]
+        decoder::<time::Date>(),
+        #[cfg(feature = "time")]
+        decoder::<time::PrimitiveDateTime>(),
+        #[cfg(feature = "time")]
+        decoder::<time::OffsetDateTime>(),
+    ];
+
+    let (_, decode) = decoders
+        .iter()
+        .find(|(type_id, _)| *type_id == TypeId::of::<U>())?;
+    decode(text)
+}
+
+type Decoder = (TypeId, fn(&str) -> Option<serde_json::Value>);
+
+fn decoder<T: Temporal>() -> Decoder {
+    (TypeId::of::<T>(), |text| {
+        serde_json::to_value(T::parse(text.trim())?).ok()
+    })
+}
+
+#[cfg(feature = "chrono")]
+mod chrono_types {
+    use super::*;
+    use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+    impl Temporal for NaiveDate {
+        const TIME_OF_DAY: bool = false;
+        const OFFSET: bool = false;
+
+        fn now() -> Self {
+            Utc::now().date_naive()
+        }
+
+        fn parse(text: &str) -> Option<Self> {
+            text.parse().ok()
+        }
+
+        #[cfg(feature = "testing")]
+        fn truncate(&self, field: DateField) -> Option<Self> {
+            Temporal::truncate(&self.and_hms_opt(0, 0, 0)?, field).map(|at| at.date())
+        }
+
+        #[cfg(feature = "testing")]
+        fn extract(&self, field: DateField) -> i64 {
+            Temporal::extract(&self.and_hms_opt(0, 0, 0).unwrap(), field)
+        }
+
+        #[cfg(feature = "testing")]
+        fn add_interval(&self, interval: Interval) -> Option<Self> {
+            Temporal::add_interval(&self.and_hms_opt(0, 0, 0)?, interval).map(|at| at.date())
+        }
+    }
+
+    impl Temporal for NaiveDateTime {
+        const TIME_OF_DAY: bool = true;
+        const OFFSET: bool = false;
+
+        fn now() -> Self {
+            Utc::now().naive_utc()
+        }
+
+        fn parse(text: &str) -> Option<Self> {
+            text.parse()
+                .or_else(|_| NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f"))
+                .ok()
+        }
+
+        #[cfg(feature = "testing")]
+        fn truncate(&self, field: DateField) -> Option<Self> {
+            use chrono::{Datelike, Timelike};
+
+            let date = self.date();
+            match field {
+                DateField::Year => NaiveDate::from_ymd_opt(date.year(), 1, 1)?.and_hms_opt(0, 0, 0),
+                DateField::Month => date.with_day(1)?.and_hms_opt(0, 0, 0),
+                DateField::Day => date.and_hms_opt(0, 0, 0),
+                DateField::Hour => date.and_hms_opt(self.hour(), 0, 0),
+                DateField::Minute => date.and_hms_opt(self.hour(), self.minute(), 0),
+                DateField::Second => date.and_hms_opt(self.hour(), self.minute(), self.second()),
+            }
+        }
+
+        #[cfg(feature = "testing")]
+        fn extract(&self, field: DateField) -> i64 {
+            use chrono::{Datelike, Timelike};
+
+            match field {
+                DateField::Year => self.year() as i64,
+                DateField::Month => self.month() as i64,
+                DateField::Day => self.day() as i64,
+                DateField::Hour => self.hour() as i64,
+                DateField::Minute => self.minute() as i64,
+                DateField::Second => self.second() as i64,
+            }
+        }
+
+        #[cfg(feature = "testing")]
+        fn add_interval(&self, interval: Interval) -> Option<Self> {
+            use chrono::{Duration, Months};
+
+            let months = Months::new(interval.months.unsigned_abs());
+            let shifted = match interval.months < 0 {
+                true => self.checked_sub_months(months)?,
+                false => self.checked_add_months(months)?,
+            };
+            shifted.checked_add_signed(
+                Duration::days(interval.days as i64) + Duration::seconds(interval.seconds),
+            )
+        }
+    }
+
+    /// SQLite writes the current time in UTC without an offset.
+    impl Temporal for DateTime<Utc> {
+        const TIME_OF_DAY: bool = true;
+        const OFFSET: bool = true;
+
+        fn now() -> Self {
+            Utc::now()
+        }
+
+        fn parse(text: &str) -> Option<Self> {
+            text.parse()
+                .ok()
+                .or_else(|| Some(Utc.from_utc_datetime(&<NaiveDateTime as Temporal>::parse(text)?)))
+        }
+
+        #[cfg(feature = "testing")]
+        fn truncate(&self, field: DateField) -> Option<Self> {
+            Some(Utc.from_utc_datetime(&self.naive_utc().truncate(field)?))
+        }
+
+        #[cfg(feature = "testing")]
+        fn extract(&self, field: DateField) -> i64 {
+            self.naive_utc().extract(field)
+        }
+
+        #[cfg(feature = "testing")]
+        fn add_interval(&self, interval: Interval) -> Option<Self> {
+            Some(Utc.from_utc_datetime(&self.naive_utc().add_interval(interval)?))
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+mod time_types {
+    use super::*;
+    use time::{Date, Format, OffsetDateTime, PrimitiveDateTime};
+
+    impl Temporal for Date {
+        const TIME_OF_DAY: bool = false;
+        const OFFSET: bool = false;
+
+        fn now() -> Self {
+            OffsetDateTime::now_utc().date()
+        }
+
+        fn parse(text: &str) -> Option<Self> {
+            Date::parse(text, "%F").ok()
+        }
+
+        #[cfg(feature = "testing")]
+        fn truncate(&self, field: DateField) -> Option<Self> {
+            self.midnight().truncate(field).map(|at| at.date())
+        }
+
+        #[cfg(feature = "testing")]
+        fn extract(&self, field: DateField) -> i64 {
+            self.midnight().extract(field)
+        }
+
+        #[cfg(feature = "testing")]
+        fn add_interval(&self, interval: Interval) -> Option<Self> {
+            self.midnight().add_interval(interval).map(|at| at.date())
+        }
+    }
+
+    /// The text is parsed as RFC 3339 in UTC, as `time` can't parse
+    /// a variable number of fractional digits in a custom format.
+    impl Temporal for PrimitiveDateTime {
+        const TIME_OF_DAY: bool = true;
+        const OFFSET: bool = false;
+
+        fn now() -> Self {
+            let now = OffsetDateTime::now_utc();
+            PrimitiveDateTime::new(now.date(), now.time())
+        }
+
+        fn parse(text: &str) -> Option<Self> {
+            let utc =
+                OffsetDateTime::parse(format!("{}Z", text.replacen(' ', "T", 1)), Format::Rfc3339)
+                    .ok()?;
+            Some(PrimitiveDateTime::new(utc.date(), utc.time()))
+        }
+
+        #[cfg(feature = "testing")]
+        fn truncate(&self, field: DateField) -> Option<Self> {
+            let date = self.date();
+            match field {
+                DateField::Year => Some(Date::try_from_ymd(date.year(), 1, 1).ok()?.midnight()),
+                DateField::Month => Some(
+                    Date::try_from_ymd(date.year(), date.month(), 1)
+                        .ok()?
+                        .midnight(),
+                ),
+                DateField::Day => Some(date.midnight()),
+                DateField::Hour => date.try_with_hms(self.hour(), 0, 0).ok(),
+                DateField::Minute => date.try_with_hms(self.hour(), self.minute(), 0).ok(),
+                DateField::Second => date
+                    .try_with_hms(self.hour(), self.minute(), self.second())
+                    .ok(),
+            }
+        }
+
+        #[cfg(feature = "testing")]
+        fn extract(&self, field: DateField) -> i64 {
+            match field {
+                DateField::Year => self.year() as i64,
+                DateField::Month => self.month() as i64,
+                DateField::Day => self.day() as i64,
+                DateField::Hour => self.hour() as i64,
+                DateField::Minute => self.minute() as i64,
+                DateField::Second => self.second() as i64,
+            }
+        }
+
+        /// `time` has no arithmetic of months, so the day is kept within the month
+        /// by taking the last day of the month `time` accepts.
+        #[cfg(feature = "testing")]
+        fn add_interval(&self, interval: Interval) -> Option<Self> {
+            let months = self.year() * 12 + self.month() as i32 - 1 + interval.months;
+            let (year, month) = (months.div_euclid(12), months.rem_euclid(12) as u8 + 1);
+            let date = (1..=self.day())
+                .rev()
+                .find_map(|day| Date::try_from_ymd(year, month, day).ok())?;
+
+            Some(
+                PrimitiveDateTime::new(date, self.time())
+                    + time::Duration::days(interval.days as i64)
+                    + time::Duration::seconds(interval.seconds),
+            )
+        }
+    }
+
+    impl Temporal for OffsetDateTime {
+        const TIME_OF_DAY: bool = true;
+        const OFFSET: bool = true;
+
+        fn now() -> Self {
+            OffsetDateTime::now_utc()
+        }
+
+        fn parse(text: &str) -> Option<Self> {
+            OffsetDateTime::parse(text.replacen(' ', "T", 1), Format::Rfc3339)
+                .ok()
+                .or_else(|| Some(<PrimitiveDateTime as Temporal>::parse(text)?.assume_utc()))
+        }
+
+        #[cfg(feature = "testing")]
+        fn truncate(&self, field: DateField) -> Option<Self> {
+            Some(utc(self).truncate(field)?.assume_utc())
+        }
+
+        #[cfg(feature = "testing")]
+        fn extract(&self, field: DateField) -> i64 {
+            utc(self).extract(field)
+        }
+
+        #[cfg(feature = "testing")]
+        fn add_interval(&self, interval: Interval) -> Option<Self> {
+            Some(utc(self).add_interval(interval)?.assume_utc())
+        }
+    }
+
+    #[cfg(feature = "testing")]
+    fn utc(at: &OffsetDateTime) -> PrimitiveDateTime {
+        let utc = at.to_offset(time::UtcOffset::UTC);
+        PrimitiveDateTime::new(utc.date(), utc.time())
+    }
+}
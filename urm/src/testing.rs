@@ -0,0 +1,225 @@
+//!
+//! An in-memory mock database, for unit-testing resolvers without a database server.
+//!
+//! `MockDatabase` answers queries from fixture rows, by evaluating the built select
+//! tree in memory, and records every query it executes:
+//!
+//! ```ignore
+//! let mock = urm::testing::MockDatabase::new().with_table(
+//!     "edition",
+//!     serde_json::json!([{ "id": 1, "publication_id": 2 }]),
+//! )?;
+//!
+//! let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+//!     .data(mock.connection::<urm::database::Postgres>())
+//!     .finish();
+//! ```
+//!
+//! The mock executes queries of any `Database`, so tables declared for a real database
+//! are tested as they are.
+//!
+//! Expressions are evaluated in memory through the `Eval` trait, see `Build::as_eval`.
+//! This module is only available with the `testing` feature.
+//!
+
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::builder::{Argument, Build, Query};
+use crate::database::{Connection, Database, Executor};
+use crate::engine::{QueryField, Select};
+use crate::quantify::Quantity;
+use crate::value::Scalar;
+use crate::{UrmError, UrmResult};
+
+///
+/// An expression which can be evaluated in memory by the `MockDatabase`.
+///
+/// An expression supporting evaluation returns itself from `Build::as_eval`.
+///
+pub trait Eval<DB: Database> {
+    fn eval(&self, scope: &Scope) -> UrmResult<Value>;
+}
+
+/// Evaluate an expression in memory, failing if the expression doesn't support that.
+pub fn eval<DB: Database>(expr: &dyn Build<DB>, scope: &Scope) -> UrmResult<Value> {
+    match expr.as_eval() {
+        Some(expr) => expr.eval(scope),
+        None => Err(UrmError::Eval(
+            "expression can't be evaluated in memory".to_string(),
+        )),
+    }
+}
+
+/// A query executed by the `MockDatabase`.
+#[derive(Clone, Debug)]
+pub struct RecordedQuery {
+    pub sql: String,
+    pub arguments: Vec<Value>,
+}
+
+///
+/// Fixture data and recorded queries, shared between clones.
+///
+#[derive(Clone, Default)]
+pub struct MockDatabase {
+    state: Arc<Mutex<MockState>>,
+}
+
+#[derive(Default)]
+struct MockState {
+    tables: BTreeMap<String, Vec<Value>>,
+    queries: Vec<RecordedQuery>,
+}
+
+impl MockDatabase {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the rows of a table, a JSON array of objects keyed by column name.
+    pub fn with_table(self, table_name: &str, rows: Value) -> UrmResult<Self> {
+        let rows = match rows {
+            Value::Array(rows) => rows,
+            _ => {
+                return Err(UrmError::Fixture(format!(
+                    "rows of `{}` must be a JSON array",
+                    table_name
+                )))
+            }
+        };
+
+        self.state
+            .lock()
+            .tables
+            .insert(table_name.to_string(), rows);
+        Ok(self)
+    }
+
+    /// A connection executing queries of the database `DB` on this mock,
+    /// to be registered as schema data.
+    pub fn connection<DB: Database>(&self) -> Connection<DB> {
+        Connection::new(self.clone())
+    }
+
+    /// The queries executed so far, in order of execution.
+    pub fn queries(&self) -> Vec<RecordedQuery> {
+        self.state.lock().queries.clone()
+    }
+}
+
+#[async_trait]
+impl<DB: Database> Executor<DB> for MockDatabase {
+    async fn fetch_rows(&self, query: Query<DB>) -> UrmResult<Vec<Value>> {
+        let arguments = query.argument_values();
+
+        let mut state = self.state.lock();
+        state.queries.push(RecordedQuery {
+            sql: query.sql,
+            arguments,
+        });
+
+        match &query.select {
+            Some(select) => eval_select(select, &state.tables, &Scope::default()),
+            None => Ok(vec![]),
+        }
+    }
+}
+
+///
+/// The rows in scope while evaluating an expression,
+/// the innermost row being the current row.
+///
+#[derive(Default)]
+pub struct Scope<'s> {
+    rows: Vec<(&'static str, &'s Value)>,
+}
+
+impl<'s> Scope<'s> {
+    fn push(&self, table_name: &'static str, row: &'s Value) -> Self {
+        let mut rows = self.rows.clone();
+        rows.push((table_name, row));
+        Self { rows }
+    }
+
+    /// Look up a column of the innermost row of the given table.
+    /// A column missing from the fixture is `null`.
+    pub fn column(&self, table_name: &str, column: &str) -> UrmResult<Value> {
+        self.rows
+            .iter()
+            .rev()
+            .find(|(name, _)| *name == table_name)
+            .map(|(_, row)| row.get(column).cloned().unwrap_or(Value::Null))
+            .ok_or_else(|| UrmError::Eval(format!("table `{}` is not in scope", table_name)))
+    }
+}
+
+fn eval_select<DB: Database>(
+    select: &Select<DB>,
+    tables: &BTreeMap<String, Vec<Value>>,
+    scope: &Scope,
+) -> UrmResult<Vec<Value>> {
+    let table_name = select.from.table.name();
+    let rows = tables.get(table_name).map(Vec::as_slice).unwrap_or(&[]);
+
+    let mut selected = vec![];
+    for row in rows {
+        let row_scope = scope.push(table_name, row);
+
+        let included = match &select.filter {
+            Some(filter) => eval(filter, &row_scope)? == Value::Bool(true),
+            None => true,
+        };
+
+        if included {
+            selected.push(row_scope);
+        }
+    }
+
+    let (limit, offset) = select.range.limit_offset();
+
+    selected
+        .into_iter()
+        .skip(offset)
+        .take(limit.unwrap_or(usize::MAX))
+        .map(|row_scope| eval_projection(select, tables, &row_scope))
+        .collect()
+}
+
+fn eval_projection<DB: Database>(
+    select: &Select<DB>,
+    tables: &BTreeMap<String, Vec<Value>>,
+    scope: &Scope,
+) -> UrmResult<Value> {
+    let mut object = serde_json::Map::new();
+
+    for (projection_key, query_field) in select.projection.lock().iter() {
+        let value = match query_field {
+            QueryField::Primitive(expr) => eval(expr.as_ref(), scope)?,
+            QueryField::Foreign { select, quantity } => {
+                let mut rows = eval_select(select, tables, scope)?;
+                match quantity {
+                    Quantity::One if rows.len() <= 1 => rows.pop().unwrap_or(Value::Null),
+                    _ => Value::Array(rows),
+                }
+            }
+        };
+
+        object.insert(projection_key.key(), value);
+    }
+
+    Ok(Value::Object(object))
+}
+
+impl<DB, T> Eval<DB> for Scalar<T>
+where
+    DB: Database,
+    T: Argument<DB> + Clone,
+{
+    fn eval(&self, _scope: &Scope) -> UrmResult<Value> {
+        Ok(self.0.to_value())
+    }
+}
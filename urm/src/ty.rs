@@ -11,6 +11,18 @@ pub trait Type: Sized + Send + Sync + 'static {
 
     /// Final, quantified output of this outcome (possibly `Vec<Self::Unit>` or some other collection).
     type Output: Send + Sync + 'static;
+
+    /// Decode the output from the JSON the database wrote for it,
+    /// which for dates and times is text parsed by their own crate, see `temporal::decode`.
+    fn decode(value: serde_json::Value) -> serde_json::Result<Self::Output>
+    where
+        Self::Output: serde::de::DeserializeOwned,
+    {
+        #[cfg(any(feature = "chrono", feature = "time"))]
+        let value = crate::temporal::decode::<Self::Unit>(&value).unwrap_or(value);
+
+        serde_json::from_value(value)
+    }
 }
 
 /// Trait implemented for types that are scalar (i.e. not a vector/collection)
@@ -98,6 +110,17 @@ where
 {
     type Unit = U;
     type Output = Vec<U>;
+
+    /// Databases without array types (as SQLite) store an array as JSON text.
+    fn decode(value: serde_json::Value) -> serde_json::Result<Self::Output>
+    where
+        Self::Output: serde::de::DeserializeOwned,
+    {
+        match value {
+            serde_json::Value::String(text) => serde_json::from_str(&text),
+            value => serde_json::from_value(value),
+        }
+    }
 }
 
 impl<U> VectorType for Vector<U> where U: Send + Sync + 'static {}
@@ -109,6 +132,93 @@ where
     type Quantify = quantify::AsSelf;
 }
 
+/// Rust types of numbers, which arithmetic operators apply to.
+pub trait Numeric: Send + Sync + 'static {
+    /// Whether division truncates towards zero.
+    const INTEGER: bool;
+}
+
+impl Numeric for i16 {
+    const INTEGER: bool = true;
+}
+
+impl Numeric for i32 {
+    const INTEGER: bool = true;
+}
+
+impl Numeric for i64 {
+    const INTEGER: bool = true;
+}
+
+impl Numeric for f32 {
+    const INTEGER: bool = false;
+}
+
+impl Numeric for f64 {
+    const INTEGER: bool = false;
+}
+
+/// Map the unit of a scalar type to `V`, keeping its nullability.
+///
+/// This is the type of a scalar function of one operand, e.g. the length of a text.
+pub trait MapUnit<V>: ScalarType {
+    type Ty: ScalarType<Unit = V>;
+}
+
+impl<U, V> MapUnit<V> for Unit<U>
+where
+    U: Send + Sync + 'static,
+    V: Send + Sync + 'static,
+{
+    type Ty = Unit<V>;
+}
+
+impl<U, V> MapUnit<V> for Nullable<U>
+where
+    U: Send + Sync + 'static,
+    V: Send + Sync + 'static,
+{
+    type Ty = Nullable<V>;
+}
+
+/// The type of a scalar function of the operands `Self` and `R` having unit `V`,
+/// which is null when either operand is null.
+pub trait JoinNullable<R, V>: ScalarType {
+    type Ty: ScalarType<Unit = V>;
+}
+
+impl<U, R, V> JoinNullable<Unit<R>, V> for Unit<U>
+where
+    U: Send + Sync + 'static,
+    V: Send + Sync + 'static,
+{
+    type Ty = Unit<V>;
+}
+
+impl<U, R, V> JoinNullable<Nullable<R>, V> for Unit<U>
+where
+    U: Send + Sync + 'static,
+    V: Send + Sync + 'static,
+{
+    type Ty = Nullable<V>;
+}
+
+impl<U, R, V> JoinNullable<Unit<R>, V> for Nullable<U>
+where
+    U: Send + Sync + 'static,
+    V: Send + Sync + 'static,
+{
+    type Ty = Nullable<V>;
+}
+
+impl<U, R, V> JoinNullable<Nullable<R>, V> for Nullable<U>
+where
+    U: Send + Sync + 'static,
+    V: Send + Sync + 'static,
+{
+    type Ty = Nullable<V>;
+}
+
 /// Type that always resolves to 'no value', i.e. Option::None
 pub struct Void<U>(std::marker::PhantomData<U>);
 
@@ -118,6 +228,18 @@ impl<U> Void<U> {
     }
 }
 
+impl<U> Clone for Void<U> {
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+
+impl<U> Default for Void<U> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<U> Type for Void<U>
 where
     U: Send + Sync + 'static,
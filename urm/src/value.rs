@@ -1,2 +1,135 @@
+use crate::builder::Argument;
+use crate::database::Database;
+
+#[derive(Clone)]
 pub struct Scalar<T>(pub T);
+
+#[derive(Clone)]
 pub struct Vector<T>(pub Vec<T>);
+
+///
+/// A Rust enum mapped to a database enum type by its labels, implemented by `#[derive(urm::SqlEnum)]`.
+///
+/// The labels and the type name follow the `#[sqlx(..)]` attributes of the enum,
+/// so they agree with `#[derive(sqlx::Type)]`. An enum argument is bound as
+/// the text of its label, cast to the enum type in databases that have one.
+///
+/// The derive implements `serde::Deserialize` from the label, and `Argument` for each
+/// database backend enabled in urm, binding the label. `Argument` is already implemented
+/// for types that are both `serde::Serialize` and `sqlx::Encode`, so an enum deriving
+/// `sqlx::Type` can't also implement `serde::Serialize`.
+///
+pub trait SqlEnum: Sized + Clone + Send + Sync + 'static {
+    /// The name of the database type, e.g. in `CREATE TYPE name AS ENUM (..)`.
+    const TYPE_NAME: &'static str;
+
+    const LABELS: &'static [&'static str];
+
+    fn label(&self) -> &'static str;
+
+    fn from_label(label: &str) -> Option<Self>;
+}
+
+/// Deserialize an enum from its label.
+pub fn deserialize_label<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: SqlEnum,
+    D: serde::Deserializer<'de>,
+{
+    let label = <String as serde::Deserialize>::deserialize(deserializer)?;
+
+    T::from_label(&label).ok_or_else(|| serde::de::Error::unknown_variant(&label, T::LABELS))
+}
+
+/// Bind the label of an enum as a text argument.
+pub fn bind_label<DB, T>(value: &T, arguments: &mut DB::Arguments)
+where
+    DB: Database,
+    T: SqlEnum,
+    String: Argument<DB>,
+{
+    value.label().to_string().bind(arguments);
+}
+
+/// Implement `Argument` for a `SqlEnum` on each enabled backend, for `#[derive(urm::SqlEnum)]`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sql_enum_arguments {
+    ($ty:ty) => {
+        $crate::__sql_enum_argument_postgres!($ty);
+        $crate::__sql_enum_argument_sqlite!($ty);
+        $crate::__sql_enum_argument_mysql!($ty);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sql_enum_argument {
+    ($db:ty, $ty:ty) => {
+        impl $crate::builder::Argument<$db> for $ty {
+            fn bind(&self, arguments: &mut <$db as $crate::database::Database>::Arguments) {
+                $crate::value::bind_label::<$db, Self>(self, arguments)
+            }
+
+            fn to_value(&self) -> $crate::serde_json::Value {
+                $crate::value::SqlEnum::label(self).into()
+            }
+
+            fn write_placeholder(&self, buf: &mut String, index: usize) {
+                <$db as $crate::database::Dialect>::write_enum_placeholder(
+                    buf,
+                    index,
+                    <Self as $crate::value::SqlEnum>::TYPE_NAME,
+                )
+            }
+        }
+    };
+}
+
+#[cfg(feature = "postgres")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sql_enum_argument_postgres {
+    ($ty:ty) => {
+        $crate::__sql_enum_argument!($crate::database::Postgres, $ty);
+    };
+}
+
+#[cfg(not(feature = "postgres"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sql_enum_argument_postgres {
+    ($ty:ty) => {};
+}
+
+#[cfg(feature = "sqlite")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sql_enum_argument_sqlite {
+    ($ty:ty) => {
+        $crate::__sql_enum_argument!($crate::database::Sqlite, $ty);
+    };
+}
+
+#[cfg(not(feature = "sqlite"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sql_enum_argument_sqlite {
+    ($ty:ty) => {};
+}
+
+#[cfg(feature = "mysql")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sql_enum_argument_mysql {
+    ($ty:ty) => {
+        $crate::__sql_enum_argument!($crate::database::MySql, $ty);
+    };
+}
+
+#[cfg(not(feature = "mysql"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sql_enum_argument_mysql {
+    ($ty:ty) => {};
+}
@@ -3,6 +3,9 @@ use urm::prelude::*;
 use urm::value::Vector;
 
 pub mod db {
+    use urm::function::Equals;
+    use urm::value::Scalar;
+
     pub struct Publication;
     pub struct Edition;
     pub struct Module;
@@ -13,6 +16,7 @@ pub mod db {
     impl Publication {
         fn all() -> [Self];
 
+        #[primary_key]
         fn id(self) -> String;
 
         #[foreign(Edition(publication_id) => Self(id))]
@@ -23,6 +27,13 @@ pub mod db {
     impl Edition {
         fn all() -> [Self];
 
+        #[filter(Equals(Self.publication_id(), Scalar(publication_id)))]
+        fn by_publication(publication_id: String) -> [Self];
+
+        #[filter(Equals(Self.id(), Scalar(id)))]
+        fn by_id(id: String) -> Option<Self>;
+
+        #[primary_key]
         fn id(self) -> String;
         fn publication_id(self) -> String;
 
@@ -48,7 +59,7 @@ pub mod db {
     }
 }
 
-/// GraphQL section
+// GraphQL section
 
 /// This object might either be the "root",
 /// or it may be the child of an edition.
@@ -118,12 +129,80 @@ impl Query {
             .probe_with(Edition, ctx)
             .await
     }
+
+    pub async fn all_editions(
+        &self,
+        ctx: &::async_graphql::Context<'_>,
+    ) -> urm::UrmResult<Vec<Edition>> {
+        db::Edition::all().probe_with(Edition, ctx).await
+    }
+
+    pub async fn publication_editions(
+        &self,
+        ctx: &::async_graphql::Context<'_>,
+        publication_id: String,
+    ) -> urm::UrmResult<Vec<Edition>> {
+        db::Edition::by_publication(publication_id)
+            .probe_with(Edition, ctx)
+            .await
+    }
+
+    pub async fn edition(
+        &self,
+        ctx: &::async_graphql::Context<'_>,
+        id: String,
+    ) -> urm::UrmResult<Option<Edition>> {
+        db::Edition::by_id(id).probe_with(Edition, ctx).await
+    }
+
+    pub async fn publication(
+        &self,
+        ctx: &::async_graphql::Context<'_>,
+        id: String,
+    ) -> urm::UrmResult<Option<Publication>> {
+        db::Publication::select_by_key(id)
+            .probe_with(Publication, ctx)
+            .await
+    }
+
+    pub async fn publications(
+        &self,
+        ctx: &::async_graphql::Context<'_>,
+        ids: Vec<String>,
+    ) -> urm::UrmResult<Vec<Publication>> {
+        db::Publication::select_by_keys(ids)
+            .probe_with(Publication, ctx)
+            .await
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use serde_json::json;
+    use urm::inspect::{FieldInspection, Inspector};
+
+    type Schema = async_graphql::Schema<
+        Query,
+        async_graphql::EmptyMutation,
+        async_graphql::EmptySubscription,
+    >;
+
+    /// A schema without a database, recording the queries it would run.
+    fn inspecting_schema() -> (Schema, Inspector) {
+        let inspector = Inspector::new();
+        let schema = async_graphql::Schema::build(
+            Query,
+            async_graphql::EmptyMutation,
+            async_graphql::EmptySubscription,
+        )
+        .data(inspector.clone())
+        .finish();
+
+        (schema, inspector)
+    }
+
     #[tokio::test]
     async fn resolve_test() -> urm::UrmResult<()> {
         let schema = async_graphql::Schema::new(
@@ -147,9 +226,106 @@ mod tests {
             )
             .await;
 
-        println!("data: {}", response.data);
-        println!("errors: {:?}", response.errors);
+        assert_eq!(response.errors.len(), 1);
+        assert_eq!(response.errors[0].message, "No database connection");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn inspects_nested_select() {
+        let (schema, inspector) = inspecting_schema();
+
+        let response = schema
+            .execute(
+                r#"{
+                    editions(ids: ["foo"]) {
+                        publication {
+                            id
+                            editions {
+                                id
+                            }
+                        }
+                    }
+                }"#,
+            )
+            .await;
+
+        assert_eq!(response.errors.len(), 1);
+        assert_eq!(response.errors[0].message, "No database connection");
+
+        let inspections = inspector.inspections();
+        assert_eq!(inspections.len(), 1);
+
+        let inspection = &inspections[0];
+        assert!(inspection.sql.starts_with("SELECT"));
+        assert!(inspection.sql.contains(r#"FROM "edition" a0"#));
+        assert!(inspection.sql.contains(r#"FROM "publication" a1"#));
+        assert!(inspection.sql.contains(r#"a0."id" = any($1)"#));
+        assert!(inspection
+            .inlined_sql
+            .contains(r#"a0."id" = any(ARRAY['foo'])"#));
+        assert_eq!(inspection.arguments, vec![json!(["foo"])]);
+
+        let select = &inspection.select;
+        assert_eq!(select.table, "edition");
+        assert_eq!(select.alias, "a0");
+        assert_eq!(select.limit, Some(20));
+        assert_eq!(select.filter.as_ref().unwrap().sql, r#"a0."id" = any($1)"#);
+
+        let publication = match &select.projection[..] {
+            [FieldInspection::Foreign { select, .. }] => select,
+            projection => panic!("unexpected projection: {:?}", projection),
+        };
+        assert_eq!(publication.table, "publication");
+        assert_eq!(
+            publication.filter.as_ref().unwrap().sql,
+            r#"a0."publication_id" = a1."id""#
+        );
+        assert_eq!(publication.projection.len(), 2);
+
+        let json = serde_json::to_value(inspection).unwrap();
+        assert_eq!(json["select"]["projection"][0]["kind"], "foreign");
+        assert_eq!(json["select"]["projection"][0]["quantity"], "one");
+    }
+
+    #[tokio::test]
+    async fn select_by_key_test() {
+        let (schema, inspector) = inspecting_schema();
+
+        schema.execute(r#"{ publication(id: "foo") { id } }"#).await;
+        schema
+            .execute(r#"{ publications(ids: ["foo", "bar"]) { id } }"#)
+            .await;
+
+        let inspections = inspector.inspections();
+        assert_eq!(inspections.len(), 2);
+
+        assert!(inspections[0].sql.contains(r#"FROM "publication" a0"#));
+        assert!(inspections[0].sql.contains(r#"a0."id" = $1"#));
+        assert!(inspections[0].inlined_sql.contains(r#"a0."id" = 'foo'"#));
+
+        assert!(inspections[1].sql.contains(r#"FROM "publication" a0"#));
+        assert!(inspections[1].sql.contains(r#"a0."id" = any($1)"#));
+        assert_eq!(inspections[1].arguments, vec![json!(["foo", "bar"])]);
+    }
+
+    #[tokio::test]
+    async fn selector_test() {
+        let (schema, inspector) = inspecting_schema();
+
+        schema.execute(r#"{ allEditions { id } }"#).await;
+        schema
+            .execute(r#"{ publicationEditions(publicationId: "foo") { id } }"#)
+            .await;
+        schema.execute(r#"{ edition(id: "foo") { id } }"#).await;
+
+        let inspections = inspector.inspections();
+        assert_eq!(inspections.len(), 3);
 
-        panic!()
+        assert!(inspections[0].sql.contains(r#"FROM "edition" a0"#));
+        assert!(inspections[0].select.filter.is_none());
+        assert!(inspections[1].sql.contains(r#"a0."publication_id" = $1"#));
+        assert!(inspections[2].sql.contains(r#"a0."id" = $1"#));
     }
 }
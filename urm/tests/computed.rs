@@ -0,0 +1,109 @@
+use urm::function::Equals;
+use urm::value::Scalar;
+
+pub mod db {
+    use urm::function::{Equals, Upper};
+
+    pub struct Contributor;
+
+    #[urm::table("contributor")]
+    impl Contributor {
+        fn id(self) -> String;
+        fn first_name(self) -> String;
+        fn last_name(self) -> String;
+        fn pen_name(self) -> Option<String>;
+
+        #[computed(Self.first_name())]
+        fn given_name(self) -> String;
+
+        #[computed(Equals(Self.first_name(), Self.last_name()))]
+        fn first_is_last(self) -> Option<bool>;
+
+        #[computed(Upper(Self.pen_name()))]
+        fn loud_pen_name(self) -> Option<String>;
+    }
+}
+
+#[derive(urm::Probe)]
+pub struct Contributor(urm::Node<db::Contributor>);
+
+#[async_graphql::Object]
+impl Contributor {
+    pub async fn given_name(&self) -> urm::UrmResult<String> {
+        urm::project(self, db::Contributor.given_name()).await
+    }
+
+    pub async fn first_is_last(&self) -> urm::UrmResult<Option<bool>> {
+        urm::project(self, db::Contributor.first_is_last()).await
+    }
+}
+
+pub struct Query;
+
+#[async_graphql::Object]
+impl Query {
+    pub async fn contributors(
+        &self,
+        ctx: &::async_graphql::Context<'_>,
+        given_name: String,
+    ) -> urm::UrmResult<Vec<Contributor>> {
+        urm::select()
+            .filter(db::Contributor.first_is_last())
+            .filter(Equals(db::Contributor.given_name(), Scalar(given_name)))
+            .probe_with(Contributor, ctx)
+            .await
+    }
+}
+
+#[tokio::test]
+async fn computed_columns_in_projection_and_filter() {
+    let inspector = urm::inspect::Inspector::new();
+    let schema = async_graphql::Schema::build(
+        Query,
+        async_graphql::EmptyMutation,
+        async_graphql::EmptySubscription,
+    )
+    .data(inspector.clone())
+    .finish();
+
+    schema
+        .execute(
+            r#"{
+                contributors(givenName: "Ursula") {
+                    givenName
+                    firstIsLast
+                }
+            }"#,
+        )
+        .await;
+
+    let inspections = inspector.inspections();
+    assert_eq!(inspections.len(), 1);
+
+    let sql = &inspections[0].sql;
+    assert!(sql.contains(r#"', (a0."first_name"),"#));
+    assert!(sql.contains(r#"', (a0."first_name" = a0."last_name")"#));
+    assert!(sql.contains(r#"(a0."first_name" = a0."last_name")"#));
+    assert!(sql.contains(r#"(a0."first_name") = $1"#));
+    assert!(inspections[0]
+        .inlined_sql
+        .contains(r#"(a0."first_name") = 'Ursula'"#));
+}
+
+/// An `Option<T>` column has the type `Nullable<T>` rather than `Unit<Option<T>>`.
+/// It still projects as `Option<T>`, and now also fits the nullable types of expressions,
+/// like `first_is_last`, and functions of `T`, like `loud_pen_name`.
+#[test]
+fn nullable_columns() {
+    use urm::ty::{Nullable, Type, Typed};
+
+    fn output<E: Typed<urm::database::Postgres>>(
+        _: E,
+    ) -> std::marker::PhantomData<<E::Ty as Type>::Output> {
+        std::marker::PhantomData
+    }
+
+    let _: urm::column::Column<db::Contributor, Nullable<String>> = db::Contributor.pen_name();
+    let _: std::marker::PhantomData<Option<String>> = output(db::Contributor.pen_name());
+    let _: std::marker::PhantomData<Option<String>> = output(db::Contributor.loud_pen_name());
+}
@@ -0,0 +1,190 @@
+use urm::prelude::*;
+use urm::probe::{ResolveSelection, SelectionDriver};
+use urm::UrmResult;
+
+pub mod db {
+    pub struct Publication;
+    pub struct Edition;
+
+    #[urm::table("publication")]
+    impl Publication {
+        #[primary_key]
+        fn id(self) -> String;
+        fn title(self) -> String;
+
+        #[foreign(Edition(publication_id) => Self(id))]
+        fn editions(self) -> [Edition];
+    }
+
+    #[urm::table("edition")]
+    impl Edition {
+        fn id(self) -> String;
+        fn publication_id(self) -> String;
+    }
+}
+
+#[derive(urm::Probe)]
+pub struct Publication(urm::Node<db::Publication>);
+
+#[derive(urm::Probe)]
+pub struct Edition(urm::Node<db::Edition>);
+
+#[async_trait::async_trait]
+impl ResolveSelection for Publication {
+    async fn resolve_selection(&self, field: &SelectionDriver<'_>) -> UrmResult<()> {
+        match field.selection().name.as_str() {
+            "title" => {
+                urm::project(self, db::Publication.title()).await?;
+            }
+            "editions" => {
+                let first = field.selection().arguments["first"].as_u64().unwrap() as usize;
+                urm::project(
+                    self,
+                    db::Publication
+                        .editions()
+                        .range(0..first)
+                        .probe_with_driver(Edition, *field),
+                )
+                .await?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl ResolveSelection for Edition {
+    async fn resolve_selection(&self, field: &SelectionDriver<'_>) -> UrmResult<()> {
+        match field.selection().name.as_str() {
+            "id" => {
+                urm::project(self, db::Edition.id()).await?;
+            }
+            "broken" => panic!("not probed"),
+            // Awaits a future which is never woken, instead of projecting
+            "stalled" => std::future::pending().await,
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+    use urm::database::Postgres;
+    use urm::probe::{Data, Selection};
+    use urm::testing::MockDatabase;
+
+    fn mock_database() -> MockDatabase {
+        MockDatabase::new()
+            .with_table(
+                "publication",
+                json!([{ "id": "p1", "title": "The Dispossessed" }]),
+            )
+            .unwrap()
+            .with_table(
+                "edition",
+                json!([
+                    { "id": "e1", "publication_id": "p1" },
+                    { "id": "e2", "publication_id": "p1" },
+                ]),
+            )
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn probes_selection_tree() {
+        let mock = mock_database();
+        let data = Data::new().with(mock.connection::<Postgres>());
+
+        let selection = Selection::new("publications")
+            .with_child(Selection::new("title"))
+            .with_child(
+                Selection::new("editions")
+                    .with_argument("first", json!(1))
+                    .with_child(Selection::new("id")),
+            );
+
+        let publications = urm::select::<db::Publication>()
+            .probe_with_driver(Publication, &SelectionDriver::new(&selection, &data))
+            .await
+            .unwrap();
+
+        assert_eq!(publications.len(), 1);
+        let title = urm::project(&publications[0], db::Publication.title())
+            .await
+            .unwrap();
+        assert_eq!(title, "The Dispossessed");
+
+        let editions = urm::project(
+            &publications[0],
+            db::Publication
+                .editions()
+                .range(0..1)
+                .probe_with_driver(Edition, SelectionDriver::new(&selection, &data)),
+        )
+        .await
+        .unwrap();
+        let id = urm::project(&editions[0], db::Edition.id()).await.unwrap();
+        assert_eq!(id, "e1");
+
+        assert_eq!(mock.queries().len(), 1);
+        assert!(mock.queries()[0].sql.contains("LIMIT 1"));
+    }
+
+    #[tokio::test]
+    async fn reports_failures_by_path() {
+        let mock = mock_database();
+        let data = Data::new().with(mock.connection::<Postgres>());
+
+        let selection = Selection::new("publications").with_child(
+            Selection::new("editions")
+                .with_alias("first")
+                .with_argument("first", json!(1))
+                .with_child(Selection::new("broken")),
+        );
+
+        let error = urm::select::<db::Publication>()
+            .probe_with_driver(Publication, &SelectionDriver::new(&selection, &data))
+            .await
+            .err()
+            .unwrap();
+
+        assert_eq!(error.code(), "PROBE");
+        assert_eq!(
+            error.to_string(),
+            "Probe error at `publications.first.broken`: panicked: not probed"
+        );
+        assert!(mock.queries().is_empty());
+    }
+
+    #[tokio::test]
+    async fn reports_resolvers_not_projecting() {
+        let mock = mock_database();
+        let data = Data::new().with(mock.connection::<Postgres>());
+
+        let selection = Selection::new("publications").with_child(
+            Selection::new("editions")
+                .with_argument("first", json!(1))
+                .with_child(Selection::new("stalled"))
+                .with_child(Selection::new("unknown")),
+        );
+
+        let error = urm::select::<db::Publication>()
+            .probe_with_driver(Publication, &SelectionDriver::new(&selection, &data))
+            .await
+            .err()
+            .unwrap();
+
+        assert_eq!(
+            error.to_string(),
+            "Probe error at \
+             `publications.editions.stalled`: did not reach `urm::project` within 1s, \
+             `publications.editions.unknown`: completed without reaching `urm::project`"
+        );
+        assert!(mock.queries().is_empty());
+    }
+}
@@ -0,0 +1,597 @@
+use urm::column::expression;
+use urm::function::{
+    AddInterval, ArrayContains, ArrayLength, Case, Cast, Coalesce, Concat, Contains, DateTrunc,
+    Equals, Extract, Length, Lower, Now, Overlaps, SubInterval, Trim, Upper,
+};
+use urm::prelude::*;
+use urm::temporal::{DateField, Interval};
+use urm::value::{Scalar, Vector};
+
+pub mod db {
+    pub struct Publication;
+    pub struct Edition;
+
+    #[urm::table("publication")]
+    impl Publication {
+        #[primary_key]
+        fn id(self) -> String;
+        fn title(self) -> String;
+
+        #[foreign(Edition(publication_id) => Self(id))]
+        fn editions(self) -> [Edition];
+
+        fn tags(self) -> Vec<String>;
+    }
+
+    #[urm::table("edition")]
+    impl Edition {
+        #[primary_key]
+        fn id(self) -> String;
+        fn publication_id(self) -> String;
+        fn printed(self) -> Option<time::PrimitiveDateTime>;
+        fn isbn(self) -> std::option::Option<String>;
+        fn languages(self) -> std::vec::Vec<String>;
+        fn cover(self) -> Vec<u8>;
+
+        #[foreign(Self(publication_id) => Publication(id))]
+        fn publication(self) -> Publication;
+    }
+}
+
+#[derive(urm::Project, Debug, PartialEq)]
+#[project(db::Publication)]
+pub struct Publication {
+    #[column(db::Publication.title())]
+    title: String,
+    #[foreign(db::Publication.editions())]
+    editions: Vec<Edition>,
+}
+
+#[derive(urm::Project, Debug, PartialEq)]
+#[project(db::Edition)]
+pub struct Edition {
+    #[column(db::Edition.id())]
+    id: String,
+}
+
+#[derive(urm::Project, Debug, PartialEq)]
+#[project(db::Edition)]
+pub struct EditionOf {
+    #[column(db::Edition.id())]
+    id: String,
+    #[foreign(db::Edition.publication())]
+    publication: Title,
+}
+
+#[derive(urm::Project, Debug, PartialEq)]
+#[project(db::Publication)]
+pub struct Title {
+    #[column(db::Publication.title())]
+    title: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+    use urm::testing::MockDatabase;
+
+    fn mock_database() -> MockDatabase {
+        MockDatabase::new()
+            .with_table(
+                "publication",
+                json!([
+                    { "id": "p1", "title": "The Dispossessed", "tags": ["utopia", "physics"] },
+                    { "id": "p2", "title": "Always Coming Home", "tags": ["utopia"] },
+                ]),
+            )
+            .unwrap()
+            .with_table(
+                "edition",
+                json!([
+                    { "id": "e1", "publication_id": "p1", "printed": "1974-05-31T10:30:45" },
+                    { "id": "e2", "publication_id": "p1", "printed": null },
+                    { "id": "e3", "publication_id": "p2", "printed": "1985-01-31 08:00:00" },
+                ]),
+            )
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn fetches_nested_projections() {
+        let mock = mock_database();
+
+        let publications = urm::select::<db::Publication>()
+            .project((
+                db::Publication.title(),
+                db::Publication
+                    .editions()
+                    .range(0..1)
+                    .project(db::Edition.id()),
+            ))
+            .fetch(&mock.connection())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            publications,
+            vec![
+                ("The Dispossessed".to_string(), vec!["e1".to_string()]),
+                ("Always Coming Home".to_string(), vec!["e3".to_string()]),
+            ]
+        );
+
+        assert_eq!(mock.queries().len(), 1);
+        assert!(mock.queries()[0].sql.contains("LIMIT 1"));
+    }
+
+    #[tokio::test]
+    async fn fetches_filtered_foreign_unit() {
+        let mock = mock_database();
+
+        let editions = urm::select::<db::Edition>()
+            .filter(Contains(
+                Vector(vec!["e2".to_string(), "e3".to_string()]),
+                db::Edition.id(),
+            ))
+            .project((
+                db::Edition.id(),
+                db::Edition.publication().project(db::Publication.title()),
+            ))
+            .fetch(&mock.connection())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            editions,
+            vec![
+                ("e2".to_string(), "The Dispossessed".to_string()),
+                ("e3".to_string(), "Always Coming Home".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_by_key() {
+        let mock = mock_database();
+
+        let title = db::Publication::select_by_key("p2".to_string())
+            .project(db::Publication.title())
+            .fetch(&mock.connection())
+            .await
+            .unwrap();
+
+        assert_eq!(title, Some("Always Coming Home".to_string()));
+    }
+
+    #[tokio::test]
+    async fn fetches_derived_projections() {
+        let mock = mock_database();
+
+        let publications = urm::select::<db::Publication>()
+            .project(Publication::projection())
+            .fetch(&mock.connection())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            publications,
+            vec![
+                Publication {
+                    title: "The Dispossessed".to_string(),
+                    editions: vec![
+                        Edition {
+                            id: "e1".to_string()
+                        },
+                        Edition {
+                            id: "e2".to_string()
+                        },
+                    ],
+                },
+                Publication {
+                    title: "Always Coming Home".to_string(),
+                    editions: vec![Edition {
+                        id: "e3".to_string()
+                    }],
+                },
+            ]
+        );
+        assert_eq!(mock.queries().len(), 1);
+
+        let edition = db::Edition::select_by_key("e3".to_string())
+            .project(EditionOf::projection())
+            .fetch(&mock.connection())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            edition,
+            Some(EditionOf {
+                id: "e3".to_string(),
+                publication: Title {
+                    title: "Always Coming Home".to_string()
+                },
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn fetches_wide_tuples() {
+        let mock = mock_database();
+
+        let publications = urm::select::<db::Publication>()
+            .range(0..1)
+            .project((
+                db::Publication.id(),
+                db::Publication.title(),
+                db::Publication.editions().project(db::Edition.id()),
+                db::Publication.id(),
+                db::Publication.title(),
+            ))
+            .fetch(&mock.connection())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            publications,
+            vec![(
+                "p1".to_string(),
+                "The Dispossessed".to_string(),
+                vec!["e1".to_string(), "e2".to_string()],
+                "p1".to_string(),
+                "The Dispossessed".to_string(),
+            )]
+        );
+
+        let p = || db::Publication.id();
+        let ids = urm::select::<db::Publication>()
+            .range(0..1)
+            .project((
+                p(),
+                p(),
+                p(),
+                p(),
+                p(),
+                p(),
+                p(),
+                p(),
+                p(),
+                p(),
+                p(),
+                p(),
+                p(),
+                p(),
+                p(),
+                p(),
+            ))
+            .fetch(&mock.connection())
+            .await
+            .unwrap();
+
+        assert_eq!(ids.len(), 1);
+        assert_eq!(ids[0].0, "p1");
+        assert_eq!(ids[0].15, "p1");
+    }
+
+    #[tokio::test]
+    async fn fetches_expressions() {
+        let mock = mock_database();
+
+        let is_p2 = || expression(Equals(db::Publication.id(), Scalar("p2".to_string())));
+
+        let publications = urm::select::<db::Publication>()
+            .project((db::Publication.title(), is_p2(), is_p2()))
+            .fetch(&mock.connection())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            publications,
+            vec![
+                ("The Dispossessed".to_string(), Some(false), Some(false)),
+                ("Always Coming Home".to_string(), Some(true), Some(true)),
+            ]
+        );
+
+        let sql = &mock.queries()[0].sql;
+        assert_eq!(sql.matches("'x").count(), 1);
+    }
+
+    #[tokio::test]
+    async fn evaluates_scalar_functions() {
+        let mock = mock_database();
+
+        let publications = urm::select::<db::Publication>()
+            .filter(Equals(
+                Lower(db::Publication.title()),
+                Scalar("always coming home".to_string()),
+            ))
+            .project((
+                expression(Upper(db::Publication.title())),
+                expression(Length(db::Publication.title())),
+                expression(Concat(db::Publication.id(), Scalar(": ".to_string()))),
+                expression(Trim(Scalar("  x ".to_string()))),
+                expression(Cast::<i64, _>::new(Scalar("42".to_string()))),
+                expression(Coalesce(db::Publication.id(), Scalar("-".to_string()))),
+            ))
+            .fetch(&mock.connection())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            publications,
+            vec![(
+                "ALWAYS COMING HOME".to_string(),
+                18,
+                "p2: ".to_string(),
+                "x".to_string(),
+                42,
+                "p2".to_string(),
+            )]
+        );
+
+        let sql = &mock.queries()[0].sql;
+        assert!(sql.contains("LOWER(a0.\"title\") = $"));
+        assert!(sql.contains("char_length(a0.\"title\")"));
+        assert!(sql.contains("(a0.\"id\" || $"));
+        assert!(sql.contains("CAST($"));
+        assert!(sql.contains(" AS BIGINT)"));
+    }
+
+    #[tokio::test]
+    async fn evaluates_case() {
+        let mock = mock_database();
+
+        let is = |id: &str| Equals(db::Publication.id(), Scalar(id.to_string()));
+        let label = |id: &str| Scalar(id.to_string());
+
+        let publications = urm::select::<db::Publication>()
+            .project((
+                expression(Case::when(is("p1"), label("first"))),
+                expression(
+                    Case::when(is("p3"), label("third"))
+                        .when(is("p2"), label("second"))
+                        .otherwise(db::Publication.title()),
+                ),
+            ))
+            .fetch(&mock.connection())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            publications,
+            vec![
+                (Some("first".to_string()), "The Dispossessed".to_string()),
+                (None, "second".to_string()),
+            ]
+        );
+
+        let sql = &mock.queries()[0].sql;
+        assert!(sql.contains("CASE\n"));
+        assert!(sql.contains("ELSE a0.\"title\""));
+    }
+
+    #[tokio::test]
+    async fn evaluates_arithmetic() {
+        let mock = mock_database();
+
+        let numbers = db::Publication::select_by_key("p1".to_string())
+            .project((
+                expression(Scalar(7) / Scalar(2)),
+                expression(Scalar(7.0) / Scalar(2.0)),
+                expression(-(Scalar(3) * Scalar(4) + Scalar(1))),
+            ))
+            .fetch(&mock.connection())
+            .await
+            .unwrap();
+
+        assert_eq!(numbers, Some((3, 3.5, -13)));
+
+        // Placeholders are numbered in the order of the projection keys.
+        let sql: String = mock.queries()[0]
+            .sql
+            .chars()
+            .filter(|c| !c.is_ascii_digit())
+            .collect();
+        assert!(sql.contains("(- (($ * $) + $))"));
+    }
+
+    #[tokio::test]
+    async fn evaluates_dates_and_times() {
+        let mock = mock_database();
+
+        let at = |(year, month, day), (hour, minute, second)| {
+            time::Date::try_from_ymd(year, month, day)
+                .unwrap()
+                .try_with_hms(hour, minute, second)
+                .unwrap()
+        };
+
+        let editions = urm::select::<db::Edition>()
+            .project((
+                db::Edition.printed(),
+                expression(DateTrunc(DateField::Month, db::Edition.printed())),
+                expression(Extract(DateField::Day, db::Edition.printed())),
+                expression(AddInterval(
+                    db::Edition.printed(),
+                    Interval::months(1) + Interval::hours(14),
+                )),
+                expression(SubInterval(db::Edition.printed(), Interval::years(1))),
+            ))
+            .fetch(&mock.connection())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            editions,
+            vec![
+                (
+                    Some(at((1974, 5, 31), (10, 30, 45))),
+                    Some(at((1974, 5, 1), (0, 0, 0))),
+                    Some(31),
+                    Some(at((1974, 7, 1), (0, 30, 45))),
+                    Some(at((1973, 5, 31), (10, 30, 45))),
+                ),
+                (None, None, None, None, None),
+                (
+                    Some(at((1985, 1, 31), (8, 0, 0))),
+                    Some(at((1985, 1, 1), (0, 0, 0))),
+                    Some(31),
+                    Some(at((1985, 2, 28), (22, 0, 0))),
+                    Some(at((1984, 1, 31), (8, 0, 0))),
+                ),
+            ]
+        );
+
+        let sql = &mock.queries()[0].sql;
+        assert!(sql.contains("date_trunc('MONTH', a0.\"printed\")"));
+        assert!(sql.contains("(a0.\"printed\" + INTERVAL '1 MONTHS 50400 SECONDS')"));
+        assert!(sql.contains("(a0.\"printed\" + INTERVAL '-12 MONTHS')"));
+    }
+
+    #[tokio::test]
+    async fn filters_by_dates_and_times() {
+        let mock = mock_database();
+
+        let editions = urm::select::<db::Edition>()
+            .filter(Equals(
+                Extract(DateField::Year, db::Edition.printed()),
+                Scalar(Some(1985i64)),
+            ))
+            .project((
+                db::Edition.id(),
+                expression(Now::<time::PrimitiveDateTime>::new()),
+            ))
+            .fetch(&mock.connection())
+            .await
+            .unwrap();
+
+        assert_eq!(editions.len(), 1);
+        assert_eq!(editions[0].0, "e3");
+        assert!(editions[0].1.year() >= 2021);
+
+        let sql = &mock.queries()[0].sql;
+        assert!(sql.contains("CAST(FLOOR(EXTRACT(YEAR FROM a0.\"printed\")) AS BIGINT)"));
+        assert!(sql.contains("(CURRENT_TIMESTAMP AT TIME ZONE 'UTC')"));
+    }
+
+    #[tokio::test]
+    async fn filters_by_array_columns() {
+        let mock = mock_database();
+        let tags = |tags: &[&str]| Vector(tags.iter().map(|tag| tag.to_string()).collect());
+
+        let publications = urm::select::<db::Publication>()
+            .filter(Contains(
+                db::Publication.tags(),
+                Scalar("physics".to_string()),
+            ))
+            .project((
+                db::Publication.tags(),
+                expression(ArrayLength(db::Publication.tags())),
+                expression(ArrayContains(
+                    db::Publication.tags(),
+                    tags(&["physics", "utopia"]),
+                )),
+                expression(Overlaps(db::Publication.tags(), tags(&["fantasy"]))),
+            ))
+            .fetch(&mock.connection())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            publications,
+            vec![(
+                vec!["utopia".to_string(), "physics".to_string()],
+                2,
+                Some(true),
+                Some(false)
+            )]
+        );
+
+        let sql = &mock.queries()[0].sql;
+        assert!(sql.contains(r#" = any(a0."tags")"#));
+        assert!(sql.contains(r#"cardinality(a0."tags")"#));
+        assert!(sql.contains(r#"(a0."tags" @> $"#));
+        assert!(sql.contains(r#"(a0."tags" && $"#));
+
+        let publications = urm::select::<db::Publication>()
+            .filter(Overlaps(
+                tags(&["utopia", "fantasy"]),
+                db::Publication.tags(),
+            ))
+            .project(db::Publication.title())
+            .fetch(&mock.connection())
+            .await
+            .unwrap();
+
+        assert_eq!(publications, vec!["The Dispossessed", "Always Coming Home"]);
+    }
+
+    #[tokio::test]
+    async fn decodes_arrays_from_text() {
+        let mock = MockDatabase::new()
+            .with_table(
+                "publication",
+                json!([
+                    { "id": "p1", "title": "[Untitled]", "tags": r#"["utopia", "physics"]"# },
+                ]),
+            )
+            .unwrap();
+
+        let publications = urm::select::<db::Publication>()
+            .project((db::Publication.title(), db::Publication.tags()))
+            .fetch(&mock.connection())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            publications,
+            vec![(
+                "[Untitled]".to_string(),
+                vec!["utopia".to_string(), "physics".to_string()]
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn expression_decode_error() {
+        let mock = MockDatabase::new()
+            .with_table("publication", json!([{ "id": "p1", "title": 42 }]))
+            .unwrap();
+
+        let error = urm::select::<db::Publication>()
+            .project(expression(db::Publication.title()))
+            .fetch(&mock.connection())
+            .await
+            .err()
+            .unwrap();
+
+        let key = mock.queries()[0]
+            .sql
+            .split('\'')
+            .nth(1)
+            .unwrap()
+            .to_string();
+        assert!(key.starts_with('x'));
+        match error {
+            urm::UrmError::Decode { table, column, .. } => {
+                assert_eq!(table, "publication");
+                assert_eq!(column, key);
+            }
+            error => panic!("expected a decode error, found {}", error),
+        }
+    }
+
+    /// `Vec` and `Option` are recognized by their last path segment,
+    /// and `Vec<u8>` is binary data rather than an array.
+    #[test]
+    fn column_types() {
+        use urm::column::Column;
+        use urm::ty::{Nullable, Unit, Vector};
+
+        let _: Column<db::Edition, Nullable<String>> = db::Edition.isbn();
+        let _: Column<db::Edition, Vector<String>> = db::Edition.languages();
+        let _: Column<db::Edition, Unit<Vec<u8>>> = db::Edition.cover();
+    }
+}
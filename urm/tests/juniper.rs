@@ -0,0 +1,292 @@
+use juniper::FieldResult;
+use urm::prelude::*;
+use urm::probe::juniper::project;
+use urm::probe::Data;
+
+pub mod db {
+    pub struct Publication;
+    pub struct Edition;
+
+    #[urm::table("publication")]
+    impl Publication {
+        #[primary_key]
+        fn id(self) -> String;
+        fn title(self) -> String;
+
+        #[foreign(Edition(publication_id) => Self(id))]
+        fn editions(self) -> [Edition];
+    }
+
+    #[urm::table("edition")]
+    impl Edition {
+        fn id(self) -> String;
+        fn publication_id(self) -> String;
+    }
+}
+
+pub struct Context {
+    data: Data,
+}
+
+impl juniper::Context for Context {}
+
+impl AsRef<Data> for Context {
+    fn as_ref(&self) -> &Data {
+        &self.data
+    }
+}
+
+#[derive(urm::Probe)]
+pub struct Publication(urm::Node<db::Publication>);
+
+#[derive(urm::Probe)]
+pub struct Edition(urm::Node<db::Edition>);
+
+#[juniper::object(Context = Context)]
+impl Publication {
+    fn title(&self) -> FieldResult<String> {
+        Ok(project(self, db::Publication.title())?)
+    }
+
+    fn editions(&self, executor: &Executor) -> FieldResult<Vec<Edition>> {
+        Ok(project(
+            self,
+            db::Publication
+                .editions()
+                .probe_with_executor(Edition, executor),
+        )?)
+    }
+
+    fn unprojected(&self) -> String {
+        "Not a column".to_string()
+    }
+}
+
+#[juniper::object(Context = Context)]
+impl Edition {
+    fn id(&self) -> FieldResult<String> {
+        Ok(project(self, db::Edition.id())?)
+    }
+
+    fn publication_id(&self) -> FieldResult<String> {
+        Ok(project(self, db::Edition.publication_id())?)
+    }
+}
+
+pub struct Query;
+
+#[juniper::object(Context = Context)]
+impl Query {
+    fn publications(executor: &Executor) -> FieldResult<Vec<Publication>> {
+        Ok(urm::select::<db::Publication>().probe_with_executor(Publication, executor)?)
+    }
+}
+
+/// The same schema in async-graphql, to compare with.
+mod graphql {
+    use super::db;
+    use urm::prelude::*;
+
+    #[derive(urm::Probe)]
+    pub struct Publication(urm::Node<db::Publication>);
+
+    #[derive(urm::Probe)]
+    pub struct Edition(urm::Node<db::Edition>);
+
+    #[async_graphql::Object]
+    impl Publication {
+        async fn title(&self) -> urm::UrmResult<String> {
+            urm::project(self, db::Publication.title()).await
+        }
+
+        async fn editions(&self, ctx: &async_graphql::Context<'_>) -> urm::UrmResult<Vec<Edition>> {
+            urm::project(self, db::Publication.editions().probe_with(Edition, ctx)).await
+        }
+    }
+
+    #[async_graphql::Object]
+    impl Edition {
+        async fn id(&self) -> urm::UrmResult<String> {
+            urm::project(self, db::Edition.id()).await
+        }
+    }
+
+    pub struct Query;
+
+    #[async_graphql::Object]
+    impl Query {
+        async fn publications(
+            &self,
+            ctx: &async_graphql::Context<'_>,
+        ) -> urm::UrmResult<Vec<Publication>> {
+            urm::select::<db::Publication>()
+                .probe_with(Publication, ctx)
+                .await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+    use urm::database::Postgres;
+    use urm::testing::MockDatabase;
+
+    type Schema = juniper::RootNode<'static, Query, juniper::EmptyMutation<Context>>;
+
+    fn execute(mock: &MockDatabase, query: &str) -> (serde_json::Value, Vec<String>) {
+        let schema = Schema::new(Query, juniper::EmptyMutation::new());
+        let context = Context {
+            data: Data::new().with(mock.connection::<Postgres>()),
+        };
+
+        let (value, errors) = futures_executor::block_on(urm::probe::juniper::execute(
+            query,
+            None,
+            &schema,
+            &juniper::Variables::new(),
+            &context,
+        ))
+        .unwrap();
+
+        (
+            serde_json::to_value(&value).unwrap(),
+            errors
+                .iter()
+                .map(|error| error.error().message().to_string())
+                .collect(),
+        )
+    }
+
+    fn mock_database() -> MockDatabase {
+        MockDatabase::new()
+            .with_table(
+                "publication",
+                json!([{ "id": "p1", "title": "The Dispossessed" }]),
+            )
+            .unwrap()
+            .with_table(
+                "edition",
+                json!([
+                    { "id": "e1", "publication_id": "p1" },
+                    { "id": "e2", "publication_id": "p1" },
+                ]),
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn resolves_look_ahead() {
+        let mock = mock_database();
+
+        let (data, errors) = execute(
+            &mock,
+            r#"{
+                publications {
+                    title
+                    ... on Publication { editions { id } }
+                }
+            }"#,
+        );
+
+        assert_eq!(errors, Vec::<String>::new());
+        assert_eq!(
+            data,
+            json!({
+                "publications": [{
+                    "title": "The Dispossessed",
+                    "editions": [{ "id": "e1" }, { "id": "e2" }],
+                }]
+            })
+        );
+        assert_eq!(mock.queries().len(), 1);
+    }
+
+    #[test]
+    fn resolves_aliases_like_async_graphql() {
+        let query = r#"{
+            books: publications {
+                name: title
+                printings: editions { key: id }
+            }
+        }"#;
+
+        let mock = mock_database();
+        let (data, errors) = execute(&mock, query);
+        assert_eq!(errors, Vec::<String>::new());
+        assert_eq!(mock.queries().len(), 1);
+
+        let graphql_mock = mock_database();
+        let schema = async_graphql::Schema::build(
+            graphql::Query,
+            async_graphql::EmptyMutation,
+            async_graphql::EmptySubscription,
+        )
+        .data(graphql_mock.connection::<Postgres>())
+        .finish();
+        let response = futures_executor::block_on(schema.execute(query));
+        assert_eq!(response.errors, vec![]);
+
+        assert_eq!(data, response.data.into_json().unwrap());
+        assert_eq!(mock.queries()[0].sql, graphql_mock.queries()[0].sql);
+        assert_eq!(
+            data,
+            json!({
+                "books": [{
+                    "name": "The Dispossessed",
+                    "printings": [{ "key": "e1" }, { "key": "e2" }],
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn resolves_repeated_fields() {
+        let mock = mock_database();
+
+        let (data, errors) = execute(
+            &mock,
+            r#"{
+                publications {
+                    title
+                    again: title
+                    editions { id }
+                    printings: editions { id publicationId }
+                }
+            }"#,
+        );
+
+        assert_eq!(errors, Vec::<String>::new());
+        assert_eq!(
+            data,
+            json!({
+                "publications": [{
+                    "title": "The Dispossessed",
+                    "again": "The Dispossessed",
+                    "editions": [{ "id": "e1" }, { "id": "e2" }],
+                    "printings": [
+                        { "id": "e1", "publicationId": "p1" },
+                        { "id": "e2", "publicationId": "p1" },
+                    ],
+                }]
+            })
+        );
+        assert_eq!(mock.queries().len(), 1);
+    }
+
+    #[test]
+    fn fails_fields_not_reaching_project() {
+        let mock = mock_database();
+
+        let (data, errors) = execute(&mock, r#"{ publications { title unprojected } }"#);
+
+        assert_eq!(
+            errors,
+            vec!["Probe error at `publications.unprojected`: completed without reaching `urm::project`"]
+        );
+        assert_eq!(data, json!(null));
+        assert!(mock.queries().is_empty());
+    }
+}
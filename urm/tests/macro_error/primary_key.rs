@@ -0,0 +1,26 @@
+pub struct Foo;
+pub struct Bar;
+pub struct Baz;
+
+#[urm::table("foo")]
+impl Foo {
+    #[primary_key]
+    fn id(self) -> String;
+
+    #[primary_key]
+    fn other_id(self) -> String;
+}
+
+#[urm::table("bar")]
+impl Bar {
+    #[primary_key]
+    fn id(self) -> Option<String>;
+}
+
+#[urm::table("baz")]
+impl Baz {
+    #[primary_key]
+    fn id(self) -> Vec<String>;
+}
+
+fn main() {}
@@ -0,0 +1,24 @@
+pub struct Foo;
+
+#[urm::table("foo")]
+impl Foo {
+    fn id(self) -> String;
+}
+
+#[derive(urm::Project)]
+struct MissingTable {
+    #[column(Foo.id())]
+    id: String,
+}
+
+#[derive(urm::Project)]
+#[project(Foo)]
+struct MissingProjection {
+    id: String,
+}
+
+#[derive(urm::Project)]
+#[project(Foo)]
+struct Unnamed(String);
+
+fn main() {}
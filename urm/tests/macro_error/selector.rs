@@ -0,0 +1,18 @@
+pub struct Foo;
+
+#[urm::table("foo")]
+impl Foo {
+    fn id(self) -> String;
+
+    fn by_id(id: String) -> [Self];
+
+    fn single() -> Self;
+
+    #[primary_key]
+    fn keyed() -> [Self];
+
+    #[filter(Self.id())]
+    fn filtered_field(self) -> String;
+}
+
+fn main() {}
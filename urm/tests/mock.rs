@@ -0,0 +1,455 @@
+use urm::function::{Contains, Equals};
+use urm::prelude::*;
+use urm::value::{Scalar, Vector};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, sqlx::Type, urm::SqlEnum, async_graphql::Enum)]
+#[sqlx(type_name = "publication_status", rename_all = "snake_case")]
+pub enum PublicationStatus {
+    Draft,
+    InPrint,
+    #[sqlx(rename = "withdrawn")]
+    OutOfPrint,
+}
+
+pub mod db {
+    pub struct Publication;
+    pub struct Edition;
+
+    #[urm::table("publication")]
+    impl Publication {
+        #[primary_key]
+        fn id(self) -> String;
+        fn title(self) -> String;
+
+        #[foreign(Edition(publication_id) => Self(id))]
+        fn editions(self) -> [Edition];
+
+        fn status(self) -> super::PublicationStatus;
+    }
+
+    #[urm::table("edition")]
+    impl Edition {
+        fn id(self) -> String;
+        fn publication_id(self) -> String;
+
+        #[foreign(Self(publication_id) => Publication(id))]
+        fn publication(self) -> Publication;
+    }
+}
+
+// GraphQL section
+
+#[derive(urm::Probe)]
+pub struct Publication(urm::Node<db::Publication>);
+
+#[derive(urm::Probe)]
+pub struct Edition(urm::Node<db::Edition>);
+
+#[async_graphql::Object]
+impl Publication {
+    pub async fn title(&self) -> async_graphql::Result<String> {
+        use async_graphql::ResultExt;
+        urm::project(self, db::Publication.title()).await.extend()
+    }
+
+    pub async fn status(&self) -> urm::UrmResult<PublicationStatus> {
+        urm::project(self, db::Publication.status()).await
+    }
+
+    pub async fn editions(
+        &self,
+        ctx: &::async_graphql::Context<'_>,
+        first: Option<usize>,
+    ) -> urm::UrmResult<Vec<Edition>> {
+        urm::project(
+            self,
+            db::Publication
+                .editions()
+                .range(None..first)
+                .probe_with(Edition, ctx),
+        )
+        .await
+    }
+}
+
+#[async_graphql::Object]
+impl Edition {
+    pub async fn id(&self) -> urm::UrmResult<String> {
+        urm::project(self, db::Edition.id()).await
+    }
+
+    pub async fn publication(
+        &self,
+        ctx: &::async_graphql::Context<'_>,
+    ) -> urm::UrmResult<Publication> {
+        urm::project(self, db::Edition.publication().probe_with(Publication, ctx)).await
+    }
+
+    pub async fn checked_id(&self) -> urm::UrmResult<String> {
+        tokio::task::yield_now().await;
+        urm::project(self, db::Edition.id()).await
+    }
+
+    pub async fn delayed_id(&self) -> urm::UrmResult<String> {
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        urm::project(self, db::Edition.id()).await
+    }
+
+    pub async fn unavailable(&self) -> async_graphql::Result<String> {
+        Err("unavailable".into())
+    }
+}
+
+pub struct Query;
+
+#[async_graphql::Object]
+impl Query {
+    pub async fn editions(
+        &self,
+        ctx: &::async_graphql::Context<'_>,
+        ids: Vec<String>,
+    ) -> urm::UrmResult<Vec<Edition>> {
+        urm::select()
+            .filter(Contains(Vector(ids), db::Edition.id()))
+            .probe_with(Edition, ctx)
+            .await
+    }
+
+    pub async fn publications(
+        &self,
+        ctx: &::async_graphql::Context<'_>,
+        status: PublicationStatus,
+    ) -> urm::UrmResult<Vec<Publication>> {
+        urm::select()
+            .filter(Equals(db::Publication.status(), Scalar(status)))
+            .probe_with(Publication, ctx)
+            .await
+    }
+
+    pub async fn publication(
+        &self,
+        ctx: &::async_graphql::Context<'_>,
+        id: String,
+    ) -> urm::UrmResult<Option<Publication>> {
+        db::Publication::select_by_key(id)
+            .probe_with(Publication, ctx)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use async_graphql::value;
+    use serde_json::json;
+    use urm::database::Postgres;
+    use urm::testing::MockDatabase;
+
+    fn mock_database() -> MockDatabase {
+        MockDatabase::new()
+            .with_table(
+                "publication",
+                json!([
+                    { "id": "p1", "title": "The Dispossessed", "status": "in_print" },
+                    { "id": "p2", "title": "Always Coming Home", "status": "withdrawn" },
+                ]),
+            )
+            .unwrap()
+            .with_table(
+                "edition",
+                json!([
+                    { "id": "e1", "publication_id": "p1" },
+                    { "id": "e2", "publication_id": "p1" },
+                    { "id": "e3", "publication_id": "p2" },
+                ]),
+            )
+            .unwrap()
+    }
+
+    fn schema(
+        mock: &MockDatabase,
+    ) -> async_graphql::Schema<Query, async_graphql::EmptyMutation, async_graphql::EmptySubscription>
+    {
+        async_graphql::Schema::build(
+            Query,
+            async_graphql::EmptyMutation,
+            async_graphql::EmptySubscription,
+        )
+        .data(mock.connection::<Postgres>())
+        .finish()
+    }
+
+    #[tokio::test]
+    async fn resolves_from_fixtures() {
+        let mock = mock_database();
+
+        let response = schema(&mock)
+            .execute(
+                r#"{
+                    editions(ids: ["e3", "e1"]) {
+                        id
+                        publication {
+                            title
+                            editions(first: 1) { id }
+                        }
+                    }
+                }"#,
+            )
+            .await;
+
+        assert_eq!(response.errors, vec![]);
+        assert_eq!(
+            response.data,
+            value!({
+                "editions": [
+                    {
+                        "id": "e1",
+                        "publication": {
+                            "title": "The Dispossessed",
+                            "editions": [{ "id": "e1" }],
+                        },
+                    },
+                    {
+                        "id": "e3",
+                        "publication": {
+                            "title": "Always Coming Home",
+                            "editions": [{ "id": "e3" }],
+                        },
+                    },
+                ]
+            })
+        );
+
+        let queries = mock.queries();
+        assert_eq!(queries.len(), 1);
+        assert!(queries[0].sql.contains(r#"a0."id" = any($1)"#));
+        assert!(queries[0].sql.contains(r#"a1."id" = a2."publication_id""#));
+        assert!(queries[0].sql.contains("LIMIT 1"));
+        assert_eq!(queries[0].arguments, vec![json!(["e3", "e1"])]);
+    }
+
+    #[tokio::test]
+    async fn select_by_key() {
+        let mock = mock_database();
+        let schema = schema(&mock);
+
+        let response = schema
+            .execute(r#"{ publication(id: "p2") { title } }"#)
+            .await;
+
+        assert_eq!(response.errors, vec![]);
+        assert_eq!(
+            response.data,
+            value!({ "publication": { "title": "Always Coming Home" } })
+        );
+
+        let response = schema
+            .execute(r#"{ publication(id: "p3") { title } }"#)
+            .await;
+
+        assert_eq!(response.errors, vec![]);
+        assert_eq!(response.data, value!({ "publication": null }));
+
+        let arguments: Vec<_> = mock
+            .queries()
+            .into_iter()
+            .map(|query| query.arguments)
+            .collect();
+        assert_eq!(arguments, vec![vec![json!("p2")], vec![json!("p3")]]);
+    }
+
+    #[tokio::test]
+    async fn decode_error() {
+        let mock = MockDatabase::new()
+            .with_table("publication", json!([{ "id": "p1", "title": 42 }]))
+            .unwrap();
+
+        let response = schema(&mock)
+            .execute(r#"{ publication(id: "p1") { title } }"#)
+            .await;
+
+        assert_eq!(response.errors.len(), 1);
+
+        let error = &response.errors[0];
+        assert!(error.message.starts_with(
+            "Could not decode publication.title as urm::ty::Unit<alloc::string::String>"
+        ));
+
+        let extensions = serde_json::to_value(&error.extensions).unwrap();
+        assert_eq!(extensions["code"], "DECODE");
+        assert_eq!(extensions["table"], "publication");
+        assert_eq!(extensions["column"], "title");
+    }
+
+    #[tokio::test]
+    async fn probe_error() {
+        let mock = mock_database();
+
+        let response = schema(&mock)
+            .execute(r#"{ editions(ids: ["e1"]) { id publication { editions { unavailable } } } }"#)
+            .await;
+
+        assert_eq!(response.errors.len(), 1);
+        assert_eq!(
+            response.errors[0].message,
+            "Probe error at `editions.publication.editions.unavailable`: unavailable"
+        );
+        assert!(mock.queries().is_empty());
+    }
+
+    #[tokio::test]
+    async fn fragments() {
+        let mock = mock_database();
+
+        let response = schema(&mock)
+            .execute(
+                r#"
+                query {
+                    editions(ids: ["e2"]) {
+                        ...EditionFields
+                        ... on Edition {
+                            publication { ... { title } }
+                        }
+                    }
+                }
+
+                fragment EditionFields on Edition { id }
+                "#,
+            )
+            .await;
+
+        assert_eq!(response.errors, vec![]);
+        assert_eq!(
+            response.data,
+            value!({
+                "editions": [
+                    { "id": "e2", "publication": { "title": "The Dispossessed" } },
+                ]
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn aliases_with_different_arguments() {
+        let mock = mock_database();
+
+        let response = schema(&mock)
+            .execute(
+                r#"{
+                    publication(id: "p1") {
+                        a: editions(first: 1) { id }
+                        b: editions { id }
+                        editions { id }
+                    }
+                }"#,
+            )
+            .await;
+
+        assert_eq!(response.errors, vec![]);
+        assert_eq!(
+            response.data,
+            value!({
+                "publication": {
+                    "a": [{ "id": "e1" }],
+                    "b": [{ "id": "e1" }, { "id": "e2" }],
+                    "editions": [{ "id": "e1" }, { "id": "e2" }],
+                }
+            })
+        );
+
+        let sql = &mock.queries()[0].sql;
+        assert!(sql.contains("LIMIT 1"));
+        assert!(sql.contains("'2:a'"));
+        assert!(sql.contains("'2:b'"));
+    }
+
+    #[tokio::test]
+    async fn awaits_before_project() {
+        let mock = mock_database();
+
+        let response = schema(&mock)
+            .execute(r#"{ editions(ids: ["e1"]) { checkedId } }"#)
+            .await;
+
+        assert_eq!(response.errors, vec![]);
+        assert_eq!(
+            response.data,
+            value!({ "editions": [{ "checkedId": "e1" }] })
+        );
+    }
+
+    #[tokio::test]
+    async fn awaits_timer_before_project() {
+        let mock = mock_database();
+
+        let response = schema(&mock)
+            .execute(r#"{ editions(ids: ["e1"]) { delayedId } }"#)
+            .await;
+
+        assert_eq!(response.errors, vec![]);
+        assert_eq!(
+            response.data,
+            value!({ "editions": [{ "delayedId": "e1" }] })
+        );
+        assert_eq!(mock.queries().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn enum_columns() {
+        let mock = mock_database();
+
+        let response = schema(&mock)
+            .execute(r#"{ publications(status: OUT_OF_PRINT) { title status } }"#)
+            .await;
+
+        assert_eq!(response.errors, vec![]);
+        assert_eq!(
+            response.data,
+            value!({
+                "publications": [
+                    { "title": "Always Coming Home", "status": "OUT_OF_PRINT" },
+                ]
+            })
+        );
+
+        let queries = mock.queries();
+        assert!(queries[0]
+            .sql
+            .contains(r#"a0."status" = CAST($1 AS "publication_status")"#));
+        assert_eq!(queries[0].arguments, vec![json!("withdrawn")]);
+    }
+
+    #[tokio::test]
+    async fn enum_decode_error() {
+        let mock = MockDatabase::new()
+            .with_table(
+                "publication",
+                json!([{ "id": "p1", "title": "The Dispossessed", "status": "lost" }]),
+            )
+            .unwrap();
+
+        let response = schema(&mock)
+            .execute(r#"{ publication(id: "p1") { status } }"#)
+            .await;
+
+        assert_eq!(response.errors.len(), 1);
+        assert!(response.errors[0]
+            .message
+            .contains("unknown variant `lost`, expected one of `draft`, `in_print`, `withdrawn`"));
+    }
+
+    #[test]
+    fn rejects_non_array_fixture() {
+        let error = MockDatabase::new()
+            .with_table("publication", json!({ "id": "p1" }))
+            .err()
+            .unwrap();
+
+        assert_eq!(error.code(), "FIXTURE");
+        assert_eq!(
+            error.to_string(),
+            "Invalid fixture: rows of `publication` must be a JSON array"
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use urm::function::Contains;
+use urm::prelude::*;
+use urm::value::Vector;
+
+pub mod db {
+    use urm::database::MySql;
+
+    pub struct Publication;
+    pub struct Edition;
+
+    #[urm::table("publication", db = MySql)]
+    impl Publication {
+        #[primary_key]
+        fn id(self) -> i32;
+
+        #[foreign(Edition(publication_id) => Self(id))]
+        fn editions(self) -> [Edition];
+    }
+
+    #[urm::table("edition", db = MySql)]
+    impl Edition {
+        fn id(self) -> i32;
+        fn publication_id(self) -> i32;
+    }
+}
+
+// GraphQL section
+
+#[derive(urm::Probe)]
+pub struct Publication(urm::Node<db::Publication>);
+
+#[derive(urm::Probe)]
+pub struct Edition(urm::Node<db::Edition>);
+
+#[async_graphql::Object]
+impl Publication {
+    pub async fn id(&self) -> urm::UrmResult<i32> {
+        urm::project(self, db::Publication.id()).await
+    }
+
+    pub async fn editions(
+        &self,
+        ctx: &::async_graphql::Context<'_>,
+        ids: Vec<i32>,
+    ) -> urm::UrmResult<Vec<Edition>> {
+        urm::project(
+            self,
+            db::Publication
+                .editions()
+                .filter(Contains(Vector(ids), db::Edition.id()))
+                .probe_with(Edition, ctx),
+        )
+        .await
+    }
+}
+
+#[async_graphql::Object]
+impl Edition {
+    pub async fn id(&self) -> urm::UrmResult<i32> {
+        urm::project(self, db::Edition.id()).await
+    }
+}
+
+pub struct Query;
+
+#[async_graphql::Object]
+impl Query {
+    pub async fn publication(
+        &self,
+        ctx: &::async_graphql::Context<'_>,
+        id: i32,
+    ) -> urm::UrmResult<Option<Publication>> {
+        db::Publication::select_by_key(id)
+            .probe_with(Publication, ctx)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn mysql_query() {
+        let inspector = urm::inspect::Inspector::new();
+        let schema = async_graphql::Schema::build(
+            Query,
+            async_graphql::EmptyMutation,
+            async_graphql::EmptySubscription,
+        )
+        .data(inspector.clone())
+        .finish();
+
+        schema
+            .execute(r#"{ publication(id: 1) { id editions(ids: [2, 3]) { id } } }"#)
+            .await;
+
+        let inspections = inspector.inspections();
+        assert_eq!(inspections.len(), 1);
+
+        let sql = &inspections[0].sql;
+        assert!(sql.starts_with("SELECT\n JSON_OBJECT("));
+        assert!(sql.contains("SELECT JSON_ARRAYAGG(r1.node)"));
+        assert!(sql.contains("a1.`id` IN (?, ?)"));
+        assert!(sql.contains("a0.`id` = ?"));
+        assert!(inspections[0].inlined_sql.contains("a1.`id` IN (2, 3)"));
+    }
+}
@@ -0,0 +1,623 @@
+use urm::function::Contains;
+use urm::prelude::*;
+use urm::value::Vector;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, sqlx::Type, urm::SqlEnum)]
+#[sqlx(rename_all = "snake_case")]
+pub enum Format {
+    Hardcover,
+    EBook,
+    PDFScan,
+}
+
+pub mod db {
+    use urm::database::Sqlite;
+
+    pub struct Publication;
+    pub struct Edition;
+
+    #[urm::table("publication", db = Sqlite)]
+    impl Publication {
+        #[primary_key]
+        fn id(self) -> i32;
+        fn title(self) -> String;
+
+        #[foreign(Edition(publication_id) => Self(id))]
+        fn editions(self) -> [Edition];
+
+        fn tags(self) -> Vec<String>;
+    }
+
+    #[urm::table("edition", db = Sqlite)]
+    impl Edition {
+        fn all() -> [Self];
+
+        #[primary_key]
+        fn id(self) -> i32;
+        fn publication_id(self) -> i32;
+        fn year(self) -> Option<i32>;
+        fn printed(self) -> Option<chrono::NaiveDateTime>;
+        fn format(self) -> super::Format;
+
+        #[foreign(Self(publication_id) => Publication(id))]
+        fn publication(self) -> Publication;
+    }
+}
+
+// GraphQL section
+
+#[derive(urm::Probe)]
+pub struct Publication(urm::Node<db::Publication>);
+
+#[derive(urm::Probe)]
+pub struct Edition(urm::Node<db::Edition>);
+
+#[async_graphql::Object]
+impl Publication {
+    pub async fn id(&self) -> urm::UrmResult<i32> {
+        urm::project(self, db::Publication.id()).await
+    }
+
+    pub async fn title(&self) -> urm::UrmResult<String> {
+        urm::project(self, db::Publication.title()).await
+    }
+
+    pub async fn editions(
+        &self,
+        ctx: &::async_graphql::Context<'_>,
+        first: Option<usize>,
+        offset: Option<usize>,
+    ) -> urm::UrmResult<Vec<Edition>> {
+        let end = first.map(|first| offset.unwrap_or(0) + first);
+
+        urm::project(
+            self,
+            db::Publication
+                .editions()
+                .range(offset..end)
+                .probe_with(Edition, ctx),
+        )
+        .await
+    }
+}
+
+#[async_graphql::Object]
+impl Edition {
+    pub async fn id(&self) -> urm::UrmResult<i32> {
+        urm::project(self, db::Edition.id()).await
+    }
+
+    pub async fn year(&self) -> urm::UrmResult<Option<i32>> {
+        urm::project(self, db::Edition.year()).await
+    }
+
+    pub async fn publication(
+        &self,
+        ctx: &::async_graphql::Context<'_>,
+    ) -> urm::UrmResult<Publication> {
+        urm::project(self, db::Edition.publication().probe_with(Publication, ctx)).await
+    }
+}
+
+pub struct Query;
+
+#[async_graphql::Object]
+impl Query {
+    pub async fn editions(
+        &self,
+        ctx: &::async_graphql::Context<'_>,
+        ids: Option<Vec<i32>>,
+    ) -> urm::UrmResult<Vec<Edition>> {
+        urm::select()
+            .filter(ids.map(|ids| Contains(Vector(ids), db::Edition.id())))
+            .probe_with(Edition, ctx)
+            .await
+    }
+
+    pub async fn first_editions(
+        &self,
+        ctx: &::async_graphql::Context<'_>,
+        first: usize,
+    ) -> urm::UrmResult<Vec<Edition>> {
+        db::Edition::all()
+            .range(0..first)
+            .probe_with(Edition, ctx)
+            .await
+    }
+
+    pub async fn publication(
+        &self,
+        ctx: &::async_graphql::Context<'_>,
+        id: i32,
+    ) -> urm::UrmResult<Option<Publication>> {
+        db::Publication::select_by_key(id)
+            .probe_with(Publication, ctx)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use async_graphql::value;
+
+    type Schema = async_graphql::Schema<
+        Query,
+        async_graphql::EmptyMutation,
+        async_graphql::EmptySubscription,
+    >;
+
+    async fn test_pool() -> sqlx::SqlitePool {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+
+        sqlx::query(
+            r#"
+            CREATE TABLE publication (
+                id INTEGER PRIMARY KEY,
+                title TEXT NOT NULL,
+                tags TEXT NOT NULL DEFAULT '[]'
+            );
+            CREATE TABLE edition (
+                id INTEGER PRIMARY KEY,
+                publication_id INTEGER NOT NULL REFERENCES publication(id),
+                year INTEGER,
+                printed TEXT,
+                format TEXT NOT NULL
+            );
+            INSERT INTO publication VALUES
+                (1, 'The Dispossessed', '["utopia", "physics"]'),
+                (2, 'Always Coming Home', '["utopia"]');
+            INSERT INTO edition VALUES
+                (10, 1, 1974, '1974-05-31 10:30:45', 'hardcover'),
+                (11, 1, NULL, NULL, 'e_book'),
+                (12, 2, 1985, '1985-01-31 08:00:00', 'pdf_scan');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        pool
+    }
+
+    async fn test_schema() -> Schema {
+        async_graphql::Schema::build(
+            Query,
+            async_graphql::EmptyMutation,
+            async_graphql::EmptySubscription,
+        )
+        .data(urm::database::Connection::new(test_pool().await))
+        .finish()
+    }
+
+    #[tokio::test]
+    async fn nested_select() {
+        let response = test_schema()
+            .await
+            .execute(
+                r#"{
+                    editions {
+                        id
+                        year
+                        publication {
+                            title
+                            editions { id }
+                        }
+                    }
+                }"#,
+            )
+            .await;
+
+        assert_eq!(response.errors, vec![]);
+        assert_eq!(
+            response.data,
+            value!({
+                "editions": [
+                    {
+                        "id": 10,
+                        "year": 1974,
+                        "publication": {
+                            "title": "The Dispossessed",
+                            "editions": [{ "id": 10 }, { "id": 11 }],
+                        },
+                    },
+                    {
+                        "id": 11,
+                        "year": null,
+                        "publication": {
+                            "title": "The Dispossessed",
+                            "editions": [{ "id": 10 }, { "id": 11 }],
+                        },
+                    },
+                    {
+                        "id": 12,
+                        "year": 1985,
+                        "publication": {
+                            "title": "Always Coming Home",
+                            "editions": [{ "id": 12 }],
+                        },
+                    },
+                ]
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn contains_filter() {
+        let response = test_schema()
+            .await
+            .execute(r#"{ editions(ids: [12, 10, 99]) { id } }"#)
+            .await;
+
+        assert_eq!(response.errors, vec![]);
+        assert_eq!(
+            response.data,
+            value!({ "editions": [{ "id": 10 }, { "id": 12 }] })
+        );
+    }
+
+    #[tokio::test]
+    async fn ranges() {
+        let response = test_schema()
+            .await
+            .execute(
+                r#"{
+                    firstEditions(first: 2) { id }
+                    publication(id: 1) {
+                        editions(first: 2, offset: 1) { id }
+                    }
+                }"#,
+            )
+            .await;
+
+        assert_eq!(response.errors, vec![]);
+        assert_eq!(
+            response.data,
+            value!({
+                "firstEditions": [{ "id": 10 }, { "id": 11 }],
+                "publication": {
+                    "editions": [{ "id": 11 }],
+                },
+            })
+        );
+
+        let response = test_schema()
+            .await
+            .execute(r#"{ publication(id: 1) { editions(first: 1, offset: 1) { id } } }"#)
+            .await;
+
+        assert_eq!(response.errors, vec![]);
+        assert_eq!(
+            response.data,
+            value!({ "publication": { "editions": [{ "id": 11 }] } })
+        );
+
+        let response = test_schema()
+            .await
+            .execute(r#"{ publication(id: 1) { editions(offset: 1) { id } } }"#)
+            .await;
+
+        assert_eq!(response.errors, vec![]);
+        assert_eq!(
+            response.data,
+            value!({ "publication": { "editions": [{ "id": 11 }] } })
+        );
+    }
+
+    #[tokio::test]
+    async fn select_by_key() {
+        let schema = test_schema().await;
+
+        let response = schema
+            .execute(r#"{ publication(id: 2) { id title editions { id } } }"#)
+            .await;
+
+        assert_eq!(response.errors, vec![]);
+        assert_eq!(
+            response.data,
+            value!({
+                "publication": {
+                    "id": 2,
+                    "title": "Always Coming Home",
+                    "editions": [{ "id": 12 }],
+                }
+            })
+        );
+
+        let response = schema.execute(r#"{ publication(id: 3) { id } }"#).await;
+
+        assert_eq!(response.errors, vec![]);
+        assert_eq!(response.data, value!({ "publication": null }));
+    }
+
+    #[tokio::test]
+    async fn fetch_without_probing() {
+        let connection = urm::database::Connection::new(test_pool().await);
+
+        let publications = urm::select::<db::Publication>()
+            .range(0..1)
+            .project((
+                db::Publication.title(),
+                db::Publication
+                    .editions()
+                    .project((db::Edition.id(), db::Edition.year())),
+            ))
+            .fetch(&connection)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            publications,
+            vec![(
+                "The Dispossessed".to_string(),
+                vec![(10, Some(1974)), (11, None)]
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn enum_columns() {
+        let pool = test_pool().await;
+        let connection = urm::database::Connection::new(pool.clone());
+
+        let editions = urm::select::<db::Edition>()
+            .filter(urm::function::Equals(
+                db::Edition.format(),
+                urm::value::Scalar(Format::PDFScan),
+            ))
+            .project((db::Edition.id(), db::Edition.format()))
+            .fetch(&connection)
+            .await
+            .unwrap();
+
+        assert_eq!(editions, vec![(12, Format::PDFScan)]);
+
+        // The labels agree with those bound by sqlx
+        for format in [Format::Hardcover, Format::EBook, Format::PDFScan] {
+            let label: String = sqlx::query_scalar("SELECT ?")
+                .bind(format)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+
+            assert_eq!(label, urm::value::SqlEnum::label(&format));
+        }
+    }
+
+    #[tokio::test]
+    async fn projects_expressions() {
+        let connection = urm::database::Connection::new(test_pool().await);
+
+        let editions = urm::select::<db::Edition>()
+            .project((
+                db::Edition.id(),
+                urm::column::expression(urm::function::Equals(
+                    db::Edition.publication_id(),
+                    urm::value::Scalar(1),
+                )),
+            ))
+            .fetch(&connection)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            editions,
+            vec![(10, Some(true)), (11, Some(true)), (12, Some(false))]
+        );
+    }
+
+    #[tokio::test]
+    async fn scalar_functions() {
+        use urm::column::expression;
+        use urm::function::{Cast, Coalesce, Concat, Equals, Length, Lower, Upper};
+        use urm::value::Scalar;
+
+        let connection = urm::database::Connection::new(test_pool().await);
+
+        let publications = urm::select::<db::Publication>()
+            .filter(Equals(
+                Lower(db::Publication.title()),
+                Scalar("the dispossessed".to_string()),
+            ))
+            .project((
+                expression(Upper(db::Publication.title())),
+                expression(Length(db::Publication.title())),
+                expression(Concat(
+                    Cast::<String, _>::new(db::Publication.id()),
+                    Scalar(". ".to_string()),
+                )),
+            ))
+            .fetch(&connection)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            publications,
+            vec![("THE DISPOSSESSED".to_string(), 16, "1. ".to_string())]
+        );
+
+        let years = urm::select::<db::Edition>()
+            .project(expression(Coalesce(db::Edition.year(), Scalar(0))))
+            .fetch(&connection)
+            .await
+            .unwrap();
+
+        assert_eq!(years, vec![1974, 0, 1985]);
+    }
+
+    #[tokio::test]
+    async fn case_expressions() {
+        use urm::column::expression;
+        use urm::function::{Case, Equals};
+        use urm::value::Scalar;
+
+        let connection = urm::database::Connection::new(test_pool().await);
+
+        let status = || {
+            Case::when(
+                Equals(db::Edition.publication_id(), Scalar(2)),
+                Scalar("later".to_string()),
+            )
+            .otherwise(Scalar("first".to_string()))
+        };
+
+        let editions = urm::select::<db::Edition>()
+            .filter(Equals(status(), Scalar("first".to_string())))
+            .project((db::Edition.id(), expression(status())))
+            .fetch(&connection)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            editions,
+            vec![(10, "first".to_string()), (11, "first".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn arithmetic() {
+        use urm::column::expression;
+        use urm::value::Scalar;
+
+        let connection = urm::database::Connection::new(test_pool().await);
+
+        let editions = urm::select::<db::Edition>()
+            .project((
+                expression(db::Edition.year() + Scalar(1)),
+                expression((db::Edition.id() * Scalar(2) - Scalar(1)) / Scalar(4)),
+                expression(-db::Edition.id()),
+            ))
+            .fetch(&connection)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            editions,
+            vec![(Some(1975), 4, -10), (None, 5, -11), (Some(1986), 5, -12),]
+        );
+    }
+
+    #[tokio::test]
+    async fn dates_and_times() {
+        use chrono::{NaiveDate, NaiveDateTime};
+        use urm::column::expression;
+        use urm::function::{AddInterval, DateTrunc, Equals, Extract, Now, SubInterval};
+        use urm::temporal::{DateField, Interval};
+        use urm::value::Scalar;
+
+        let connection = urm::database::Connection::new(test_pool().await);
+
+        let at = |(year, month, day), (hour, minute, second)| {
+            NaiveDate::from_ymd_opt(year, month, day)
+                .unwrap()
+                .and_hms_opt(hour, minute, second)
+                .unwrap()
+        };
+
+        let editions = urm::select::<db::Edition>()
+            .project((
+                db::Edition.printed(),
+                expression(DateTrunc(DateField::Month, db::Edition.printed())),
+                expression(Extract(DateField::Day, db::Edition.printed())),
+                expression(AddInterval(
+                    db::Edition.printed(),
+                    Interval::days(1) + Interval::hours(14),
+                )),
+                expression(SubInterval(db::Edition.printed(), Interval::years(1))),
+            ))
+            .fetch(&connection)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            editions,
+            vec![
+                (
+                    Some(at((1974, 5, 31), (10, 30, 45))),
+                    Some(at((1974, 5, 1), (0, 0, 0))),
+                    Some(31),
+                    Some(at((1974, 6, 2), (0, 30, 45))),
+                    Some(at((1973, 5, 31), (10, 30, 45))),
+                ),
+                (None, None, None, None, None),
+                (
+                    Some(at((1985, 1, 31), (8, 0, 0))),
+                    Some(at((1985, 1, 1), (0, 0, 0))),
+                    Some(31),
+                    Some(at((1985, 2, 1), (22, 0, 0))),
+                    Some(at((1984, 1, 31), (8, 0, 0))),
+                ),
+            ]
+        );
+
+        let printed_in_1985 = urm::select::<db::Edition>()
+            .filter(Equals(
+                Extract(DateField::Year, db::Edition.printed()),
+                Scalar(Some(1985i64)),
+            ))
+            .project((db::Edition.id(), expression(Now::<NaiveDateTime>::new())))
+            .fetch(&connection)
+            .await
+            .unwrap();
+
+        assert_eq!(printed_in_1985.len(), 1);
+        assert_eq!(printed_in_1985[0].0, 12);
+        assert!(printed_in_1985[0].1 > at((2021, 1, 1), (0, 0, 0)));
+    }
+
+    #[tokio::test]
+    async fn array_columns() {
+        use urm::column::expression;
+        use urm::function::{ArrayContains, ArrayLength, Overlaps};
+        use urm::value::Scalar;
+
+        let connection = urm::database::Connection::new(test_pool().await);
+        let tags = |tags: &[&str]| Vector(tags.iter().map(|tag| tag.to_string()).collect());
+
+        let publications = urm::select::<db::Publication>()
+            .project((
+                db::Publication.tags(),
+                expression(ArrayLength(db::Publication.tags())),
+                expression(ArrayContains(db::Publication.tags(), tags(&["physics"]))),
+                expression(Overlaps(
+                    db::Publication.tags(),
+                    tags(&["utopia", "fantasy"]),
+                )),
+            ))
+            .fetch(&connection)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            publications,
+            vec![
+                (
+                    vec!["utopia".to_string(), "physics".to_string()],
+                    2,
+                    Some(true),
+                    Some(true)
+                ),
+                (vec!["utopia".to_string()], 1, Some(false), Some(true)),
+            ]
+        );
+
+        let titles = urm::select::<db::Publication>()
+            .filter(Contains(
+                db::Publication.tags(),
+                Scalar("physics".to_string()),
+            ))
+            .project(db::Publication.title())
+            .fetch(&connection)
+            .await
+            .unwrap();
+
+        assert_eq!(titles, vec!["The Dispossessed"]);
+    }
+}
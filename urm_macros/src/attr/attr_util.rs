@@ -8,3 +8,24 @@ pub fn attr_has_simple_ident(attr: &syn::Attribute, name: &str) -> bool {
 
     segment.ident == name
 }
+
+/// Parse a single parenthesized expression, like in `#[name(expr)]`.
+pub fn parse_paren_expr(
+    input: syn::parse::ParseStream,
+    name: &str,
+) -> syn::Result<(proc_macro2::Span, syn::Expr)> {
+    let content;
+    let _paren_token = syn::parenthesized!(content in input);
+    let content_span = content.span();
+
+    let expr: syn::Expr = content.parse()?;
+
+    if !content.is_empty() {
+        return Err(syn::Error::new(
+            content.span(),
+            format!("Expected a single {} expression", name),
+        ));
+    }
+
+    Ok((content_span, expr))
+}
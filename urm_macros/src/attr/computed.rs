@@ -0,0 +1,17 @@
+use proc_macro2::Span;
+use syn::parse::ParseStream;
+
+use crate::attr::attr_util;
+
+pub struct Computed {
+    pub span: Span,
+    pub expr: syn::Expr,
+}
+
+impl syn::parse::Parse for Computed {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let (span, expr) = attr_util::parse_paren_expr(input, "computed")?;
+
+        Ok(Self { span, expr })
+    }
+}
@@ -0,0 +1,17 @@
+use proc_macro2::Span;
+use syn::parse::ParseStream;
+
+use crate::attr::attr_util;
+
+pub struct Filter {
+    pub span: Span,
+    pub expr: syn::Expr,
+}
+
+impl syn::parse::Parse for Filter {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let (span, expr) = attr_util::parse_paren_expr(input, "filter")?;
+
+        Ok(Self { span, expr })
+    }
+}
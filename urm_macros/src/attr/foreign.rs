@@ -61,10 +61,10 @@ impl syn::parse::Parse for Foreign {
             }),
             (Table::Zelf, Table::Zelf) => Err(syn::Error::new(
                 content_span,
-                format!("Self-referential not supported (yet?)"),
+                "Self-referential not supported (yet?)",
             )),
             (Table::Foreign(_), Table::Foreign(_)) => {
-                Err(syn::Error::new(content_span, format!("No Self(..) found")))
+                Err(syn::Error::new(content_span, "No Self(..) found"))
             }
         }
     }
@@ -100,20 +100,20 @@ fn create_column_eq_predicates(
     if local.len() != foreign.len() {
         return Err(syn::Error::new(
             content_span,
-            format!("Must have the same number of columns in self and foreign"),
+            "Must have the same number of columns in self and foreign",
         ));
     }
 
-    if local.len() == 0 {
+    if local.is_empty() {
         return Err(syn::Error::new(
             content_span,
-            format!("Must specify at least one field"),
+            "Must specify at least one field",
         ));
     }
 
     Ok(local
         .into_iter()
-        .zip(foreign.into_iter())
+        .zip(foreign)
         .map(|(local_ident, foreign_ident)| ColumnEqPredicate {
             local_ident,
             foreign_ident,
@@ -0,0 +1,151 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::spanned::Spanned;
+
+use crate::attr::attr_util;
+
+pub struct ProjectStruct {
+    pub ident: syn::Ident,
+    pub table_ty: syn::Type,
+    pub fields: Vec<ProjectField>,
+}
+
+pub struct ProjectField {
+    pub ident: syn::Ident,
+    pub projection: FieldProjection,
+}
+
+pub enum FieldProjection {
+    /// `#[column(expr)]`, a column (or other projection) deserialized directly.
+    Column(syn::Expr),
+    /// `#[foreign(expr)]`, a foreign projection into a nested `Project` struct.
+    Foreign(syn::Expr, Box<syn::Type>),
+}
+
+impl syn::parse::Parse for ProjectStruct {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let item: syn::ItemStruct = input.parse()?;
+
+        let mut table_ty = None;
+        for attr in &item.attrs {
+            if attr_util::attr_has_simple_ident(attr, "project") {
+                table_ty = Some(attr.parse_args::<syn::Type>()?);
+            }
+        }
+
+        let table_ty = table_ty
+            .ok_or_else(|| syn::Error::new(item.ident.span(), "Expected #[project(Table)]"))?;
+
+        let named = match &item.fields {
+            syn::Fields::Named(named) => named,
+            _ => return Err(syn::Error::new(item.fields.span(), "Expected named fields")),
+        };
+
+        let fields = named
+            .named
+            .iter()
+            .map(ProjectField::from_field)
+            .collect::<syn::Result<Vec<_>>>()?;
+
+        Ok(Self {
+            ident: item.ident,
+            table_ty,
+            fields,
+        })
+    }
+}
+
+impl ProjectField {
+    fn from_field(field: &syn::Field) -> syn::Result<Self> {
+        let ident = field.ident.clone().unwrap();
+        let mut projection = None;
+
+        for attr in &field.attrs {
+            let next = if attr_util::attr_has_simple_ident(attr, "column") {
+                FieldProjection::Column(attr.parse_args()?)
+            } else if attr_util::attr_has_simple_ident(attr, "foreign") {
+                FieldProjection::Foreign(attr.parse_args()?, Box::new(nested_struct_ty(&field.ty)))
+            } else {
+                continue;
+            };
+
+            if projection.replace(next).is_some() {
+                return Err(syn::Error::new(
+                    attr.span(),
+                    "Expected a single #[column(..)] or #[foreign(..)]",
+                ));
+            }
+        }
+
+        let projection = projection.ok_or_else(|| {
+            syn::Error::new(ident.span(), "Expected #[column(..)] or #[foreign(..)]")
+        })?;
+
+        Ok(Self { ident, projection })
+    }
+}
+
+/// The nested struct of a foreign field, i.e. `T` in `T`, `Option<T>` or `Vec<T>`.
+fn nested_struct_ty(ty: &syn::Type) -> syn::Type {
+    if let syn::Type::Path(type_path) = ty {
+        let last_segment = type_path.path.segments.last().unwrap();
+
+        if last_segment.ident == "Vec" || last_segment.ident == "Option" {
+            if let syn::PathArguments::AngleBracketed(args) = &last_segment.arguments {
+                if let Some(syn::GenericArgument::Type(inner)) = args.args.first() {
+                    return inner.clone();
+                }
+            }
+        }
+    }
+
+    ty.clone()
+}
+
+pub fn gen_project(project_struct: ProjectStruct) -> TokenStream {
+    let ident = &project_struct.ident;
+    let table_ty = &project_struct.table_ty;
+
+    let field_idents: Vec<_> = project_struct
+        .fields
+        .iter()
+        .map(|field| &field.ident)
+        .collect();
+
+    let projections = project_struct.fields.iter().map(|field| {
+        let ident = &field.ident;
+        let projection = match &field.projection {
+            FieldProjection::Column(expr) => quote! { #expr },
+            FieldProjection::Foreign(expr, nested_ty) => quote! {
+                ::urm::foreign::ProjectForeign::project(
+                    #expr,
+                    <#nested_ty as ::urm::Project>::projection()
+                )
+            },
+        };
+
+        quote! {
+            let #ident = node.project_field(#projection).await?;
+        }
+    });
+
+    quote! {
+        #[::urm::async_trait]
+        impl ::urm::Project for #ident {
+            type Table = #table_ty;
+
+            async fn project_fields(
+                node: &::urm::Node<Self::Table>
+            ) -> ::urm::UrmResult<Option<Self>> {
+                #(#projections)*
+
+                Ok(match (#(#field_idents,)*) {
+                    (#(Some(#field_idents),)*) => Some(Self {
+                        #(#field_idents,)*
+                    }),
+                    _ => None,
+                })
+            }
+        }
+    }
+}
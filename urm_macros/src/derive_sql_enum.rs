@@ -0,0 +1,220 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::spanned::Spanned;
+
+use crate::attr::attr_util;
+
+pub struct SqlEnum {
+    pub ident: syn::Ident,
+    pub type_name: String,
+    pub variants: Vec<SqlEnumVariant>,
+}
+
+pub struct SqlEnumVariant {
+    pub ident: syn::Ident,
+    pub label: String,
+}
+
+/// The `#[sqlx(..)]` options relevant for the labels of an enum.
+#[derive(Default)]
+struct SqlxOptions {
+    type_name: Option<String>,
+    rename_all: Option<String>,
+    rename: Option<String>,
+}
+
+impl syn::parse::Parse for SqlEnum {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let item: syn::ItemEnum = input.parse()?;
+        let options = SqlxOptions::from_attrs(&item.attrs)?;
+
+        let variants = item
+            .variants
+            .iter()
+            .map(|variant| {
+                if !matches!(variant.fields, syn::Fields::Unit) {
+                    return Err(syn::Error::new(
+                        variant.fields.span(),
+                        "Expected a variant without fields",
+                    ));
+                }
+
+                let label = match SqlxOptions::from_attrs(&variant.attrs)?.rename {
+                    Some(rename) => rename,
+                    None => rename_all(&variant.ident.to_string(), options.rename_all.as_deref())
+                        .map_err(|message| syn::Error::new(variant.ident.span(), message))?,
+                };
+
+                Ok(SqlEnumVariant {
+                    ident: variant.ident.clone(),
+                    label,
+                })
+            })
+            .collect::<syn::Result<Vec<_>>>()?;
+
+        Ok(Self {
+            type_name: options.type_name.unwrap_or_else(|| item.ident.to_string()),
+            ident: item.ident,
+            variants,
+        })
+    }
+}
+
+impl SqlxOptions {
+    fn from_attrs(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut options = Self::default();
+
+        for attr in attrs {
+            if !attr_util::attr_has_simple_ident(attr, "sqlx") {
+                continue;
+            }
+
+            let list = match attr.parse_meta()? {
+                syn::Meta::List(list) => list,
+                meta => return Err(syn::Error::new(meta.span(), "Expected #[sqlx(..)]")),
+            };
+
+            for nested in list.nested {
+                if let syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+                    path,
+                    lit: syn::Lit::Str(value),
+                    ..
+                })) = nested
+                {
+                    if path.is_ident("type_name") {
+                        options.type_name = Some(value.value());
+                    } else if path.is_ident("rename_all") {
+                        options.rename_all = Some(value.value());
+                    } else if path.is_ident("rename") {
+                        options.rename = Some(value.value());
+                    }
+                }
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+/// The label of a variant, following the `rename_all` rules of `sqlx`.
+fn rename_all(ident: &str, rule: Option<&str>) -> Result<String, String> {
+    let words = split_words(ident);
+    let capitalize = |word: &str| {
+        let mut chars = word.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => String::new(),
+        }
+    };
+
+    Ok(match rule {
+        None => ident.to_string(),
+        Some("lowercase") => ident.to_lowercase(),
+        Some("UPPERCASE") => ident.to_uppercase(),
+        Some("snake_case") => words.join("_"),
+        Some("SCREAMING_SNAKE_CASE") => words.join("_").to_uppercase(),
+        Some("kebab-case") => words.join("-"),
+        Some("camelCase") => {
+            let mut words = words.iter();
+            let first = words.next().cloned().unwrap_or_default();
+            first + &words.map(|word| capitalize(word)).collect::<String>()
+        }
+        Some("PascalCase") => words.iter().map(|word| capitalize(word)).collect(),
+        Some(rule) => return Err(format!("Unknown rename_all rule \"{}\"", rule)),
+    })
+}
+
+/// The lowercase words of an identifier, split the way `heck` splits them for `sqlx`,
+/// so that e.g. `HTTPServer` is `http_server` in `snake_case`.
+fn split_words(ident: &str) -> Vec<String> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Case {
+        Boundary,
+        Lowercase,
+        Uppercase,
+    }
+
+    let mut words = vec![];
+
+    for part in ident.split(|c: char| !c.is_alphanumeric()) {
+        let mut start = 0;
+        let mut case = Case::Boundary;
+        let mut chars = part.char_indices().peekable();
+
+        while let Some((index, c)) = chars.next() {
+            let (next_index, next) = match chars.peek() {
+                Some(&next) => next,
+                None => break,
+            };
+
+            // Digits continue the case of the characters before them
+            let next_case = if c.is_lowercase() {
+                Case::Lowercase
+            } else if c.is_uppercase() {
+                Case::Uppercase
+            } else {
+                case
+            };
+
+            if next_case == Case::Lowercase && next.is_uppercase() {
+                // `inPrint`: a word ends before an uppercase letter following a lowercase one
+                words.push(&part[start..next_index]);
+                start = next_index;
+                case = Case::Boundary;
+            } else if case == Case::Uppercase && c.is_uppercase() && next.is_lowercase() {
+                // `HTTPServer`: the last letter of an uppercase run starts the next word
+                words.push(&part[start..index]);
+                start = index;
+                case = Case::Boundary;
+            } else {
+                case = next_case;
+            }
+        }
+
+        words.push(&part[start..]);
+    }
+
+    words
+        .into_iter()
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+pub fn gen_sql_enum(sql_enum: SqlEnum) -> TokenStream {
+    let ident = &sql_enum.ident;
+    let type_name = &sql_enum.type_name;
+    let variant_idents: Vec<_> = sql_enum.variants.iter().map(|v| &v.ident).collect();
+    let labels: Vec<_> = sql_enum.variants.iter().map(|v| &v.label).collect();
+
+    quote! {
+        impl ::urm::value::SqlEnum for #ident {
+            const TYPE_NAME: &'static str = #type_name;
+            const LABELS: &'static [&'static str] = &[#(#labels),*];
+
+            fn label(&self) -> &'static str {
+                match self {
+                    #(Self::#variant_idents => #labels,)*
+                }
+            }
+
+            fn from_label(label: &str) -> Option<Self> {
+                match label {
+                    #(#labels => Some(Self::#variant_idents),)*
+                    _ => None,
+                }
+            }
+        }
+
+        impl<'de> ::urm::serde::Deserialize<'de> for #ident {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: ::urm::serde::Deserializer<'de>,
+            {
+                ::urm::value::deserialize_label(deserializer)
+            }
+        }
+
+        ::urm::__sql_enum_arguments!(#ident);
+    }
+}
@@ -3,11 +3,15 @@
 extern crate proc_macro;
 
 mod derive_probe;
+mod derive_project;
+mod derive_sql_enum;
 mod table;
 mod table_method;
 
 mod attr {
     pub mod attr_util;
+    pub mod computed;
+    pub mod filter;
     pub mod foreign;
 }
 
@@ -16,13 +20,10 @@ use quote::quote;
 
 #[proc_macro_attribute]
 pub fn table(args: TokenStream, input: TokenStream) -> TokenStream {
-    let name: syn::LitStr = syn::parse_macro_input!(args as syn::LitStr);
-
-    println!("derive table for {:?}", name);
-
+    let table_args = syn::parse_macro_input!(args as table::TableArgs);
     let impl_table = syn::parse_macro_input!(input as table::ImplTable);
 
-    let tokens = table::gen_table(name, impl_table);
+    let tokens = table::gen_table(table_args, impl_table);
 
     TokenStream::from(tokens)
 }
@@ -46,3 +47,17 @@ pub fn derive_probe(input: TokenStream) -> TokenStream {
 
     TokenStream::from(tokens)
 }
+
+#[proc_macro_derive(Project, attributes(project, column, foreign))]
+pub fn derive_project(input: TokenStream) -> TokenStream {
+    let project_struct = syn::parse_macro_input!(input as derive_project::ProjectStruct);
+
+    TokenStream::from(derive_project::gen_project(project_struct))
+}
+
+#[proc_macro_derive(SqlEnum, attributes(sqlx))]
+pub fn derive_sql_enum(input: TokenStream) -> TokenStream {
+    let sql_enum = syn::parse_macro_input!(input as derive_sql_enum::SqlEnum);
+
+    TokenStream::from(derive_sql_enum::gen_sql_enum(sql_enum))
+}
@@ -1,8 +1,34 @@
-use quote::quote;
+use quote::{quote, quote_spanned};
 use syn::parse::ParseStream;
 
 use crate::table_method;
 
+/// Arguments to the table attribute: `#[table("name")]` or `#[table("name", db = path)]`.
+pub struct TableArgs {
+    pub name: syn::LitStr,
+    pub db: Option<syn::Path>,
+}
+
+impl syn::parse::Parse for TableArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name: syn::LitStr = input.parse()?;
+        let mut db = None;
+
+        if input.parse::<Option<syn::token::Comma>>()?.is_some() && !input.is_empty() {
+            let ident: syn::Ident = input.parse()?;
+            if ident != "db" {
+                return Err(syn::Error::new(ident.span(), "Expected `db = <path>`"));
+            }
+
+            let _: syn::token::Eq = input.parse()?;
+            db = Some(input.parse()?);
+            let _: Option<syn::token::Comma> = input.parse()?;
+        }
+
+        Ok(TableArgs { name, db })
+    }
+}
+
 pub struct ImplTable {
     pub path: syn::Path,
     pub mod_ident: syn::Ident,
@@ -34,7 +60,12 @@ impl syn::parse::Parse for ImplTable {
     }
 }
 
-pub fn gen_table(table_name: syn::LitStr, impl_table: ImplTable) -> proc_macro2::TokenStream {
+pub fn gen_table(table_args: TableArgs, impl_table: ImplTable) -> proc_macro2::TokenStream {
+    let table_name = &table_args.name;
+    let db = match &table_args.db {
+        Some(db) => quote! { #db },
+        None => quote! { ::urm::database::Postgres },
+    };
     let path = &impl_table.path;
     let mod_ident = &impl_table.mod_ident;
 
@@ -43,9 +74,11 @@ pub fn gen_table(table_name: syn::LitStr, impl_table: ImplTable) -> proc_macro2:
         .iter()
         .map(|method| table_method::gen_method(method, &impl_table));
 
+    let primary_key_methods = gen_primary_key_methods(&impl_table);
+
     quote! {
         impl ::urm::Table for #path {
-            type DB = ::urm::database::Postgres;
+            type DB = #db;
 
             fn name(&self) -> &'static str {
                 #table_name
@@ -54,6 +87,8 @@ pub fn gen_table(table_name: syn::LitStr, impl_table: ImplTable) -> proc_macro2:
 
         impl #path {
             #(#field_methods)*
+
+            #primary_key_methods
         }
 
         mod #mod_ident {
@@ -70,3 +105,60 @@ pub fn gen_table(table_name: syn::LitStr, impl_table: ImplTable) -> proc_macro2:
         }
     }
 }
+
+/// Generate the by-key lookups of a table having a `#[primary_key]` field.
+fn gen_primary_key_methods(impl_table: &ImplTable) -> proc_macro2::TokenStream {
+    let mut key_fields = impl_table.methods.iter().filter_map(|method| match method {
+        table_method::Method::Field(field) => field.meta.primary_key.map(|span| (span, field)),
+        _ => None,
+    });
+
+    let (span, field) = match key_fields.next() {
+        Some(key_field) => key_field,
+        None => return quote! {},
+    };
+
+    if let Some((span, _)) = key_fields.next() {
+        return syn::Error::new(span, "Only one primary key is supported").to_compile_error();
+    }
+
+    let key_ty = match field.primary_key_type() {
+        Ok(key_ty) => key_ty,
+        Err(error) => return error.to_compile_error(),
+    };
+
+    let path = &impl_table.path;
+    let method_ident = &field.method_ident;
+
+    quote_spanned! {span=>
+        /// Select the single row having the given primary key.
+        pub fn select_by_key(key: #key_ty) -> ::urm::Select<
+            Self,
+            impl ::urm::lower::Lower<<#path as ::urm::Table>::DB>
+                + ::urm::ty::ScalarTyped<<#path as ::urm::Table>::DB, bool>
+                + Clone,
+            ::urm::quantify::AsOption
+        > {
+            ::urm::select::<Self>()
+                .filter(::urm::function::Equals(
+                    Self.#method_ident(),
+                    ::urm::value::Scalar(key)
+                ))
+                .single()
+        }
+
+        /// Select all rows having one of the given primary keys.
+        pub fn select_by_keys(keys: Vec<#key_ty>) -> ::urm::Select<
+            Self,
+            impl ::urm::lower::Lower<<#path as ::urm::Table>::DB>
+                + ::urm::ty::ScalarTyped<<#path as ::urm::Table>::DB, bool>
+                + Clone
+        > {
+            ::urm::select::<Self>()
+                .filter(::urm::function::Contains(
+                    ::urm::value::Vector(keys),
+                    Self.#method_ident()
+                ))
+        }
+    }
+}
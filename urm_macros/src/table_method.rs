@@ -3,19 +3,35 @@ use quote::*;
 use syn::spanned::Spanned;
 
 use crate::attr::attr_util;
+use crate::attr::computed;
+use crate::attr::filter;
 use crate::attr::foreign;
 
 pub enum Method {
-    /// An associated function (without a `self`) becomes a 'selector':
-    /// TODO: Don't need this, with the Filter mechanism
-    Selector,
+    /// An associated function (without a `self`) becomes a 'selector',
+    /// a named root select optionally using a `#[filter(..)]`:
+    Selector(Box<Selector>),
     /// A proper method with `self` receiver becomes a Field:
-    Field(Field),
+    Field(Box<Field>),
     Error(syn::Error),
 }
 
+pub struct Selector {
+    pub method_ident: syn::Ident,
+    pub inputs: syn::punctuated::Punctuated<syn::FnArg, syn::token::Comma>,
+    pub filter: Option<filter::Filter>,
+    quantify: SelectorQuantify,
+}
+
+/// How many rows a selector selects
+enum SelectorQuantify {
+    /// `[Self]`
+    Vec,
+    /// `Option<Self>`
+    Option,
+}
+
 pub struct Field {
-    pub span: proc_macro2::Span,
     pub field_idx: usize,
     pub field_name: syn::LitStr,
     pub method_ident: syn::Ident,
@@ -35,6 +51,36 @@ enum ReturnType {
     Zelf(syn::Ident),
 }
 
+impl Field {
+    /// The key type of a `#[primary_key]` field, which must be a non-nullable unit type.
+    pub fn primary_key_type(&self) -> syn::Result<&syn::TypePath> {
+        match &self.return_type {
+            Quantified::Unit(ReturnType::Path(path)) => {
+                if option_inner_type(path).is_some() {
+                    Err(syn::Error::new(
+                        path.span(),
+                        "A primary key cannot be nullable",
+                    ))
+                } else if array_inner_type(path).is_some() {
+                    Err(syn::Error::new(
+                        path.span(),
+                        "A primary key cannot be an array",
+                    ))
+                } else {
+                    Ok(path)
+                }
+            }
+            Quantified::Unit(ReturnType::Zelf(zelf)) => {
+                Err(syn::Error::new(zelf.span(), "Expected a type, not Self"))
+            }
+            Quantified::Slice(bracket, _) => Err(syn::Error::new(
+                bracket.span,
+                "Expected non-slice unit type",
+            )),
+        }
+    }
+}
+
 impl syn::spanned::Spanned for ReturnType {
     fn span(&self) -> Span {
         match self {
@@ -46,6 +92,9 @@ impl syn::spanned::Spanned for ReturnType {
 
 pub struct Meta {
     pub foreign: Option<foreign::Foreign>,
+    pub computed: Option<computed::Computed>,
+    pub primary_key: Option<Span>,
+    pub filter: Option<filter::Filter>,
 }
 
 impl Method {
@@ -65,6 +114,13 @@ impl Method {
 
         match method.sig.inputs.first() {
             Some(syn::FnArg::Receiver(_)) => {
+                if let Some(filter) = &meta.filter {
+                    return Err(syn::Error::new(
+                        filter.span,
+                        "A filter is only valid on selectors (functions without self)",
+                    ));
+                }
+
                 let field_name = method.sig.ident.to_string();
                 let mut method_chars = field_name.chars();
                 let struct_ident = quote::format_ident!(
@@ -79,8 +135,7 @@ impl Method {
 
                 let field_name = syn::LitStr::new(&field_name, method.sig.ident.span());
 
-                Ok(Self::Field(Field {
-                    span,
+                Ok(Self::Field(Box::new(Field {
                     field_idx,
                     field_name,
                     method_ident: method.sig.ident,
@@ -88,9 +143,54 @@ impl Method {
                     meta,
                     inputs: method.sig.inputs,
                     return_type,
-                }))
+                })))
+            }
+            _ => {
+                let quantify = Self::selector_quantify(&return_type)?;
+                let Meta {
+                    foreign,
+                    computed,
+                    primary_key,
+                    filter,
+                } = meta;
+
+                if let Some(span) = foreign
+                    .map(|foreign| foreign.span)
+                    .or_else(|| computed.map(|computed| computed.span))
+                    .or(primary_key)
+                {
+                    return Err(syn::Error::new(
+                        span,
+                        "Only filter is valid on selectors (functions without self)",
+                    ));
+                }
+
+                if filter.is_none() && !method.sig.inputs.is_empty() {
+                    return Err(syn::Error::new(
+                        method.sig.inputs.span(),
+                        "Selector arguments must be used by a #[filter(..)]",
+                    ));
+                }
+
+                Ok(Self::Selector(Box::new(Selector {
+                    method_ident: method.sig.ident,
+                    inputs: method.sig.inputs,
+                    filter,
+                    quantify,
+                })))
+            }
+        }
+    }
+
+    fn selector_quantify(return_type: &Quantified<ReturnType>) -> syn::Result<SelectorQuantify> {
+        match return_type {
+            Quantified::Slice(_, ReturnType::Zelf(_)) => Ok(SelectorQuantify::Vec),
+            Quantified::Unit(ReturnType::Path(path)) if is_option_of_self(path) => {
+                Ok(SelectorQuantify::Option)
             }
-            _ => Ok(Self::Selector),
+            Quantified::Unit(return_type) | Quantified::Slice(_, return_type) => Err(
+                syn::Error::new(return_type.span(), "Expected [Self] or Option<Self>"),
+            ),
         }
     }
 
@@ -127,21 +227,51 @@ impl Method {
                     Ok(ReturnType::Path(path))
                 }
             }
-            _ => return Err(syn::Error::new(ty.span(), "Expected simple Path-like type")),
+            _ => Err(syn::Error::new(ty.span(), "Expected simple Path-like type")),
         }
     }
 }
 
 fn meta_from_attrs(attrs: Vec<syn::Attribute>) -> syn::Result<Meta> {
-    let mut meta = Meta { foreign: None };
+    let mut meta = Meta {
+        foreign: None,
+        computed: None,
+        primary_key: None,
+        filter: None,
+    };
 
     for attr in attrs {
         if attr_util::attr_has_simple_ident(&attr, "foreign") {
             meta.foreign = Some(syn::parse2(attr.tokens)?);
+        } else if attr_util::attr_has_simple_ident(&attr, "computed") {
+            meta.computed = Some(syn::parse2(attr.tokens)?);
+        } else if attr_util::attr_has_simple_ident(&attr, "filter") {
+            meta.filter = Some(syn::parse2(attr.tokens)?);
+        } else if attr_util::attr_has_simple_ident(&attr, "primary_key") {
+            if !attr.tokens.is_empty() {
+                return Err(syn::Error::new(
+                    attr.tokens.span(),
+                    "primary_key takes no arguments",
+                ));
+            }
+            meta.primary_key = Some(attr.path.span());
         } else {
+            return Err(syn::Error::new(attr.path.span(), "Unrecognized attribute"));
+        }
+    }
+
+    if let (Some(_), Some(computed)) = (&meta.foreign, &meta.computed) {
+        return Err(syn::Error::new(
+            computed.span,
+            "A computed field cannot also be foreign",
+        ));
+    }
+
+    if let Some(span) = meta.primary_key {
+        if meta.foreign.is_some() || meta.computed.is_some() {
             return Err(syn::Error::new(
-                attr.path.span(),
-                format!("Unrecognized attribute"),
+                span,
+                "A primary key must be a stored column",
             ));
         }
     }
@@ -149,12 +279,73 @@ fn meta_from_attrs(attrs: Vec<syn::Attribute>) -> syn::Result<Meta> {
     Ok(meta)
 }
 
+/// The urm `Type` of a unit (non-slice) return type.
+/// `Option<T>` becomes `Nullable<T>`, `Vec<T>` (an array column) becomes `Vector<T>`,
+/// anything else is `Unit<T>`, including `Vec<u8>`, which is binary data.
+fn unit_type(path: &syn::TypePath) -> proc_macro2::TokenStream {
+    if let Some(inner) = option_inner_type(path) {
+        quote! { ::urm::ty::Nullable<#inner> }
+    } else if let Some(inner) = array_inner_type(path) {
+        quote! { ::urm::ty::Vector<#inner> }
+    } else {
+        quote! { ::urm::ty::Unit<#path> }
+    }
+}
+
+fn is_self_type(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(path) => path.qself.is_none() && path.path.is_ident("Self"),
+        _ => false,
+    }
+}
+
+fn is_option_of_self(path: &syn::TypePath) -> bool {
+    match option_inner_type(path) {
+        Some(ty) => is_self_type(ty),
+        None => false,
+    }
+}
+
+fn option_inner_type(path: &syn::TypePath) -> Option<&syn::Type> {
+    generic_inner_type(path, "Option")
+}
+
+/// The element type of an array column `Vec<T>`, unless it is the binary `Vec<u8>`.
+fn array_inner_type(path: &syn::TypePath) -> Option<&syn::Type> {
+    generic_inner_type(path, "Vec").filter(|inner| match inner {
+        syn::Type::Path(inner) => !(inner.qself.is_none() && inner.path.is_ident("u8")),
+        _ => true,
+    })
+}
+
+/// The type argument of `Ident<T>`, matched on the last segment, e.g. `std::vec::Vec<T>`.
+fn generic_inner_type<'p>(path: &'p syn::TypePath, ident: &str) -> Option<&'p syn::Type> {
+    if path.qself.is_some() {
+        return None;
+    }
+
+    let segment = path.path.segments.last()?;
+    if segment.ident != ident {
+        return None;
+    }
+
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) if args.args.len() == 1 => {
+            match args.args.first().unwrap() {
+                syn::GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
 pub fn gen_method(
     method: &Method,
     impl_table: &crate::table::ImplTable,
 ) -> proc_macro2::TokenStream {
     let field = match method {
-        Method::Selector => return quote! {},
+        Method::Selector(selector) => return gen_selector(selector, impl_table),
         Method::Field(field) => field,
         Method::Error(error) => return error.to_compile_error(),
     };
@@ -174,6 +365,8 @@ pub fn gen_method(
         }
     };
 
+    let field_id = field.field_idx as u16;
+
     if let Some(foreign) = &field.meta.foreign {
         let span = foreign.span;
         let foreign_table_path = &foreign.foreign_table_path;
@@ -235,28 +428,48 @@ pub fn gen_method(
                 #local_table_path,
                 #foreign_table_path,
                 ::urm::foreign::#outcome<#output_type>,
-                impl ::urm::lower::Lower<::urm::database::Postgres> + ::urm::ty::ScalarTyped<::urm::database::Postgres, bool>,
+                impl ::urm::lower::Lower<<#local_table_path as ::urm::Table>::DB>
+                    + ::urm::ty::ScalarTyped<<#local_table_path as ::urm::Table>::DB, bool>
+                    + Clone,
                 ()
             > {
-                urm::foreign::foreign(#eq_pred)
+                ::urm::foreign::foreign(::urm::project::LocalId(#field_id), #eq_pred)
             }
         }
     } else {
         let field_name = &field.field_name;
-        let field_id = field.field_idx as u16;
 
         let ty = match &field.return_type {
             Quantified::Unit(return_type) => match return_type {
                 ReturnType::Zelf(zelf) => {
                     syn::Error::new(zelf.span(), "Expected a type, not Self").to_compile_error()
                 }
-                ReturnType::Path(path) => quote! { ::urm::ty::Unit<#path> },
+                ReturnType::Path(path) => unit_type(path),
             },
             Quantified::Slice(bracket, _) => {
                 syn::Error::new(bracket.span, "Expected non-slice unit type").to_compile_error()
             }
         };
 
+        if let Some(computed) = &field.meta.computed {
+            let span = computed.span;
+            let expr = &computed.expr;
+
+            return quote_spanned! {span=>
+                pub fn #method_ident(#inputs) -> ::urm::column::Computed<
+                    #local_table_path,
+                    impl ::urm::lower::Lower<<#local_table_path as ::urm::Table>::DB, Ty = #ty>
+                        + ::urm::builder::Build<<#local_table_path as ::urm::Table>::DB>
+                > {
+                    ::urm::column::Computed::new(
+                        #field_name,
+                        ::urm::project::LocalId(#field_id),
+                        #expr
+                    )
+                }
+            };
+        }
+
         quote! {
             pub fn #method_ident(#inputs) -> ::urm::column::Column<#local_table_path, #ty> {
                 ::urm::column::Column::new(
@@ -267,3 +480,38 @@ pub fn gen_method(
         }
     }
 }
+
+fn gen_selector(
+    selector: &Selector,
+    impl_table: &crate::table::ImplTable,
+) -> proc_macro2::TokenStream {
+    let method_ident = &selector.method_ident;
+    let inputs = &selector.inputs;
+    let local_table_path = &impl_table.path;
+
+    let filter = selector.filter.as_ref().map(|filter| {
+        let span = filter.span;
+        let expr = &filter.expr;
+
+        quote_spanned! {span=> .filter(#expr) }
+    });
+
+    let (quantify, single) = match selector.quantify {
+        SelectorQuantify::Vec => (quote! { ::urm::quantify::AsVec }, quote! {}),
+        SelectorQuantify::Option => (quote! { ::urm::quantify::AsOption }, quote! { .single() }),
+    };
+
+    quote! {
+        pub fn #method_ident(#inputs) -> ::urm::Select<
+            #local_table_path,
+            impl ::urm::lower::Lower<<#local_table_path as ::urm::Table>::DB>
+                + ::urm::ty::ScalarTyped<<#local_table_path as ::urm::Table>::DB, bool>
+                + Clone,
+            #quantify
+        > {
+            ::urm::select::<#local_table_path>()
+                #filter
+                #single
+        }
+    }
+}